@@ -1,33 +1,268 @@
-pub fn process_part1(input: &str) -> String {
-    let result = input
-        .split("\n\n")
-        .map(|elf_calories| {
-            elf_calories
-                .lines()
-                .map(|item| item.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .max()
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    fmt::{self, Display, Write},
+    ops::{Div, Mul},
+    str::FromStr,
+};
+
+/// Number of elves listed by [`report`]
+pub const REPORT_SIZE: usize = 10;
+
+/// Integer type the calories are counted in
+pub trait Calories:
+    Copy + Ord + Default + Display + FromStr + Div<Output = Self> + Mul<Output = Self>
+{
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! impl_calories {
+    ($($t:ty),*) => {
+        $(
+            impl Calories for $t {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_calories!(u32, u64, u128);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InventoryError {
+    /// The item on `line` is negative
+    NegativeItem { line: usize },
+    /// The item on `line` isn't a number
+    InvalidItem { line: usize, value: String },
+    /// The total of the elf with the given 1-based index doesn't fit, it overflowed at `line`
+    Overflow { elf: usize, line: usize },
+}
+
+impl Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InventoryError::NegativeItem { line } => {
+                write!(f, "line {}: calories can't be negative", line)
+            }
+            InventoryError::InvalidItem { line, value } => {
+                write!(f, "line {}: `{}` is not a number of calories", line, value)
+            }
+            InventoryError::Overflow { elf, line } => {
+                write!(f, "line {}: the total of elf {} overflows", line, elf)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InventoryError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf<T = u32> {
+    /// Position of the elf in the input, starting at 1
+    pub index: usize,
+    /// Line of the elf's first item, starting at 1
+    pub line: usize,
+    pub items: Vec<T>,
+    total: T,
+}
+
+impl<T: Calories> Elf<T> {
+    pub fn total(&self) -> T {
+        self.total
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inventory<T = u32> {
+    elves: Vec<Elf<T>>,
+}
+
+impl<T: Calories> Inventory<T> {
+    /// Parses blank line separated blocks of calories, one block per elf
+    pub fn parse(input: &str) -> Result<Inventory<T>, InventoryError> {
+        let mut elves: Vec<Elf<T>> = vec![];
+        let mut current: Option<Elf<T>> = None;
+
+        for (i, item) in input.lines().enumerate() {
+            let line = i + 1;
+            let item = item.trim();
+
+            if item.is_empty() {
+                elves.extend(current.take());
+                continue;
+            }
+
+            let calories = item.parse::<T>().map_err(|_| {
+                let negative = item.strip_prefix('-').is_some_and(|digits| {
+                    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+                });
+                if negative {
+                    InventoryError::NegativeItem { line }
+                } else {
+                    InventoryError::InvalidItem {
+                        line,
+                        value: item.to_string(),
+                    }
+                }
+            })?;
+
+            let elf = current.get_or_insert_with(|| Elf {
+                index: elves.len() + 1,
+                line,
+                items: vec![],
+                total: T::default(),
+            });
+            elf.total = elf
+                .total
+                .checked_add(calories)
+                .ok_or(InventoryError::Overflow {
+                    elf: elf.index,
+                    line,
+                })?;
+            elf.items.push(calories);
+        }
+        elves.extend(current);
+
+        Ok(Inventory { elves })
+    }
+
+    pub fn elves(&self) -> &[Elf<T>] {
+        &self.elves
+    }
+
+    /// Looks up an elf by its 1-based index
+    pub fn elf(&self, index: usize) -> Option<&Elf<T>> {
+        index.checked_sub(1).and_then(|i| self.elves.get(i))
+    }
+
+    /// The `n` elves carrying the most calories, most first. Ties go to the elf listed first.
+    pub fn top(&self, n: usize) -> Vec<&Elf<T>> {
+        // min-heap of the best `n` seen so far, the weakest of them is on top
+        let mut heap = BinaryHeap::with_capacity(n + 1);
+        for (i, elf) in self.elves.iter().enumerate() {
+            heap.push(Reverse((elf.total(), Reverse(i))));
+            if heap.len() > n {
+                heap.pop();
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((_, Reverse(i)))| &self.elves[i])
+            .collect()
+    }
+
+    /// Sum of the totals of the `n` elves carrying the most calories, `None` if it overflows
+    pub fn top_total(&self, n: usize) -> Option<T> {
+        self.top(n)
+            .iter()
+            .try_fold(T::default(), |sum, elf| sum.checked_add(elf.total()))
+    }
+
+    /// Rank of the elf with the given 1-based index, elves with equal totals share a rank
+    pub fn rank(&self, index: usize) -> Option<usize> {
+        let total = self.elf(index)?.total();
+        Some(self.elves.iter().filter(|elf| elf.total() > total).count() + 1)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.elves.is_empty() {
+            return None;
+        }
+        let sum = self
+            .elves
+            .iter()
+            .map(|elf| elf.total().as_f64())
+            .sum::<f64>();
+        Some(sum / self.elves.len() as f64)
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        let mut totals = self.elves.iter().map(Elf::total).collect::<Vec<_>>();
+        totals.sort_unstable();
+
+        let middle = totals.len() / 2;
+        match totals.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(totals[middle].as_f64()),
+            _ => Some((totals[middle - 1].as_f64() + totals[middle].as_f64()) / 2.0),
+        }
+    }
+
+    /// Counts the elves per `bucket_width` wide range of totals, keyed by the start of the range.
+    /// Empty ranges are left out. `None` for a zero width.
+    pub fn histogram(&self, bucket_width: T) -> Option<BTreeMap<T, usize>> {
+        if bucket_width == T::default() {
+            return None;
+        }
+
+        let mut histogram = BTreeMap::new();
+        for elf in self.elves.iter() {
+            let bucket = elf.total() / bucket_width * bucket_width;
+            *histogram.entry(bucket).or_insert(0) += 1;
+        }
+        Some(histogram)
+    }
+}
+
+fn parse(input: &str) -> Inventory<u64> {
+    Inventory::parse(input).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Ranked listing of the elves carrying the most calories, followed by some statistics
+pub fn report(input: &str) -> String {
+    let inventory = parse(input);
+    let mut report = String::new();
+
+    for elf in inventory.top(REPORT_SIZE) {
+        writeln!(
+            report,
+            "#{:<3} elf {:<4} {:>7} calories in {} items",
+            inventory.rank(elf.index).unwrap(),
+            elf.index,
+            elf.total(),
+            elf.items.len()
+        )
         .unwrap();
+    }
+
+    writeln!(report, "\nelves:  {}", inventory.elves().len()).unwrap();
+    if let (Some(mean), Some(median)) = (inventory.mean(), inventory.median()) {
+        writeln!(report, "mean:   {:.1}", mean).unwrap();
+        writeln!(report, "median: {:.1}", median).unwrap();
+    }
 
-    result.to_string()
+    writeln!(report, "\ncalories").unwrap();
+    for (bucket, count) in inventory.histogram(10_000).expect("the width isn't zero") {
+        writeln!(report, "{:>6}+ {}", bucket, "#".repeat(count)).unwrap();
+    }
+
+    report
 }
 
-pub fn process_part2(input: &str) -> String {
-    let mut result = input
-        .split("\n\n")
-        .map(|elf_calories| {
-            elf_calories
-                .lines()
-                .map(|item| item.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .collect::<Vec<u32>>();
+pub fn process_part1(input: &str) -> String {
+    let inventory = parse(input);
 
-    result.sort_by(|a, b| b.cmp(a));
-    let result = result.iter().take(3).sum::<u32>();
+    inventory
+        .top_total(1)
+        .expect("total calories overflow")
+        .to_string()
+}
 
-    result.to_string()
+pub fn process_part2(input: &str) -> String {
+    let inventory = parse(input);
+
+    inventory
+        .top_total(3)
+        .expect("total calories overflow")
+        .to_string()
 }
 
 #[cfg(test)]
@@ -60,4 +295,63 @@ mod tests {
         let result = process_part2(&INPUT);
         assert_eq!(result, "45000");
     }
+
+    #[test]
+    fn top_works() {
+        let inventory = Inventory::<u32>::parse(INPUT).unwrap();
+        let top = inventory.top(2);
+
+        assert_eq!(top[0].index, 4);
+        assert_eq!(top[0].line, 10);
+        assert_eq!(top[0].items, vec![7000, 8000, 9000]);
+        assert_eq!(top[1].index, 3);
+        assert_eq!(inventory.top(10).len(), 5);
+    }
+
+    #[test]
+    fn statistics_work() {
+        let inventory = Inventory::<u32>::parse(INPUT).unwrap();
+
+        assert_eq!(inventory.rank(4), Some(1));
+        assert_eq!(inventory.rank(2), Some(5));
+        assert_eq!(inventory.rank(6), None);
+        assert_eq!(inventory.mean(), Some(11000.0));
+        assert_eq!(inventory.median(), Some(10000.0));
+        assert_eq!(
+            inventory.histogram(10_000),
+            Some(BTreeMap::from([(0, 2), (10_000, 2), (20_000, 1)]))
+        );
+        assert_eq!(inventory.histogram(0), None);
+    }
+
+    #[test]
+    fn overflow_is_detected() {
+        const INPUT: &str = "1\n\n4000000000\n300000000\n";
+
+        assert_eq!(
+            Inventory::<u32>::parse(INPUT),
+            Err(InventoryError::Overflow { elf: 2, line: 4 })
+        );
+
+        let inventory = Inventory::<u64>::parse(INPUT).unwrap();
+        assert_eq!(inventory.top_total(1), Some(4_300_000_000));
+
+        let inventory = Inventory::<u32>::parse("4000000000\n\n300000000").unwrap();
+        assert_eq!(inventory.top_total(2), None);
+    }
+
+    #[test]
+    fn invalid_items_are_rejected() {
+        assert_eq!(
+            Inventory::<u32>::parse("1000\n\n-200"),
+            Err(InventoryError::NegativeItem { line: 3 })
+        );
+        assert_eq!(
+            Inventory::<u128>::parse("1000\n12a"),
+            Err(InventoryError::InvalidItem {
+                line: 2,
+                value: String::from("12a")
+            })
+        );
+    }
 }
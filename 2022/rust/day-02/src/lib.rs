@@ -1,93 +1,375 @@
-use std::{cmp::Ordering, str::FromStr};
+pub mod tournament;
 
-#[derive(PartialEq, Clone, Copy)]
-enum Move {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+use std::{cmp::Reverse, collections::BTreeMap};
+
+/// Index of a shape within its [`Game`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shape(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
 }
 
-impl FromStr for Move {
-    type Err = String;
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "A" | "X" => Ok(Move::Rock),
-            "B" | "Y" => Ok(Move::Paper),
-            "C" | "Z" => Ok(Move::Scissors),
-            _ => Err("Not a known move".to_string()),
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutcomeScores {
+    pub loss: u32,
+    pub draw: u32,
+    pub win: u32,
+}
+
+impl Default for OutcomeScores {
+    fn default() -> Self {
+        OutcomeScores {
+            loss: 0,
+            draw: 3,
+            win: 6,
         }
     }
 }
 
-impl PartialOrd for Move {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self == &Move::Scissors && other == &Move::Rock {
-            Some(Ordering::Less)
-        } else if self == &Move::Rock && other == &Move::Scissors {
-            Some(Ordering::Greater)
+impl OutcomeScores {
+    pub fn score(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::Loss => self.loss,
+            Outcome::Draw => self.draw,
+            Outcome::Win => self.win,
+        }
+    }
+}
+
+/// A game where every shape beats some shapes and loses to all the others,
+/// like Rock-Paper-Scissors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    names: Vec<String>,
+    shape_scores: Vec<u32>,
+    /// `beats[a][b]` is true when shape `a` beats shape `b`
+    beats: Vec<Vec<bool>>,
+    outcome_scores: OutcomeScores,
+}
+
+impl Game {
+    /// Checks that every pair of different shapes has exactly one winner
+    pub fn new(
+        names: Vec<String>,
+        shape_scores: Vec<u32>,
+        beats: Vec<Vec<bool>>,
+        outcome_scores: OutcomeScores,
+    ) -> Result<Game, String> {
+        let len = names.len();
+        if shape_scores.len() != len
+            || beats.len() != len
+            || beats.iter().any(|row| row.len() != len)
+        {
+            return Err(String::from(
+                "every shape needs a name, a score and a row of the beats relation",
+            ));
+        }
+
+        for a in 0..len {
+            if beats[a][a] {
+                return Err(format!("{} can't beat itself", names[a]));
+            }
+            for b in a + 1..len {
+                if beats[a][b] == beats[b][a] {
+                    return Err(format!(
+                        "exactly one of {} and {} has to win",
+                        names[a], names[b]
+                    ));
+                }
+            }
+        }
+
+        Ok(Game {
+            names,
+            shape_scores,
+            beats,
+            outcome_scores,
+        })
+    }
+
+    /// Builds a balanced game over an odd number of shapes: listed in order, each shape beats
+    /// the half of the other shapes right before it, wrapping around
+    pub fn cyclic(
+        names: &[&str],
+        shape_scores: Vec<u32>,
+        outcome_scores: OutcomeScores,
+    ) -> Result<Game, String> {
+        let len = names.len();
+        if len.is_multiple_of(2) {
+            return Err(String::from("a cyclic game needs an odd number of shapes"));
+        }
+
+        let beats = (0..len)
+            .map(|a| {
+                (0..len)
+                    .map(|b| (1..=len / 2).contains(&((a + len - b) % len)))
+                    .collect()
+            })
+            .collect();
+
+        Game::new(
+            names.iter().map(|name| name.to_string()).collect(),
+            shape_scores,
+            beats,
+            outcome_scores,
+        )
+    }
+
+    /// The game as played in 2022's day 2
+    pub fn rock_paper_scissors() -> Game {
+        Game::cyclic(
+            &["Rock", "Paper", "Scissors"],
+            vec![1, 2, 3],
+            OutcomeScores::default(),
+        )
+        .unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Game {
+        Game::cyclic(
+            &["Rock", "Spock", "Paper", "Lizard", "Scissors"],
+            vec![1, 2, 3, 4, 5],
+            OutcomeScores::default(),
+        )
+        .unwrap()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.len()).map(Shape)
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.names[shape.0]
+    }
+
+    pub fn shape(&self, name: &str) -> Option<Shape> {
+        self.names.iter().position(|n| n == name).map(Shape)
+    }
+
+    /// Outcome of a round for the player playing `me`
+    pub fn outcome(&self, me: Shape, opponent: Shape) -> Outcome {
+        if me == opponent {
+            Outcome::Draw
+        } else if self.beats[me.0][opponent.0] {
+            Outcome::Win
         } else {
-            Some((*self as u8).cmp(&(*other as u8)))
+            Outcome::Loss
         }
     }
+
+    /// Score of a round for the player playing `me`
+    pub fn score(&self, me: Shape, opponent: Shape) -> u32 {
+        self.shape_scores[me.0] + self.outcome_scores.score(self.outcome(me, opponent))
+    }
+
+    /// The best scoring shape that ends the round with `outcome`
+    pub fn shape_for(&self, opponent: Shape, outcome: Outcome) -> Option<Shape> {
+        self.shapes()
+            .filter(|&me| self.outcome(me, opponent) == outcome)
+            .max_by_key(|&me| self.shape_scores[me.0])
+    }
+}
+
+/// Reads the `index`-th letter counting from `first`, e.g. `B` from `A` is 1
+fn letter_index(letter: &str, first: char) -> Option<usize> {
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c >= first => Some(c as usize - first as usize),
+        _ => None,
+    }
+}
+
+/// Splits a round into the opponent's shape (`A`, `B`, ...) and the index of the response
+/// (`X`, `Y`, ...), whose meaning depends on the part
+fn parse_round(game: &Game, line: &str) -> (Shape, usize) {
+    let (opponent, response) = line.split_once(' ').expect("round should have two columns");
+
+    let opponent = letter_index(opponent, 'A')
+        .filter(|&index| index < game.len())
+        .expect("not a known move");
+    let response = letter_index(response, 'X').expect("unexpected response");
+
+    (Shape(opponent), response)
 }
 
 pub fn process_part1(input: &str) -> u32 {
-    let result = input
+    let game = Game::rock_paper_scissors();
+
+    input
         .lines()
         .map(|line| {
-            let moves: Vec<Move> = line
-                .split(" ")
-                .map(|s| s.parse::<Move>().unwrap())
-                .collect();
-            match moves[0].partial_cmp(&moves[1]) {
-                Some(Ordering::Equal) => 3 + moves[1] as u32,
-                Some(Ordering::Greater) => 0 + moves[1] as u32,
-                Some(Ordering::Less) => 6 + moves[1] as u32,
-                None => {
-                    panic!("moves should be comparable")
-                }
-            }
+            let (opponent, me) = parse_round(&game, line);
+            assert!(me < game.len(), "unexpected response");
+            game.score(Shape(me), opponent)
         })
-        .sum::<u32>();
-
-    result
+        .sum::<u32>()
 }
 
 pub fn process_part2(input: &str) -> u32 {
-    let result = input
+    let game = Game::rock_paper_scissors();
+
+    input
         .lines()
         .map(|line| {
-            let moves: Vec<&str> = line.split(" ").collect();
-            let opponent_move = moves[0].parse::<Move>().unwrap();
-            match moves[1] {
-                "X" => {
-                    let required_move = match opponent_move {
-                        Move::Rock => Move::Scissors,
-                        Move::Paper => Move::Rock,
-                        Move::Scissors => Move::Paper,
-                    };
-                    0 + required_move as u32
-                }
-                "Y" => 3 + opponent_move as u32,
-
-                "Z" => {
-                    let required_move = match opponent_move {
-                        Move::Rock => Move::Paper,
-                        Move::Paper => Move::Scissors,
-                        Move::Scissors => Move::Rock,
-                    };
-                    6 + required_move as u32
-                }
+            let (opponent, outcome) = parse_round(&game, line);
+            let outcome = *Outcome::ALL.get(outcome).expect("unexpected response");
+            let me = game.shape_for(opponent, outcome).unwrap();
+            game.score(me, opponent)
+        })
+        .sum::<u32>()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpretation {
+    /// The response is the shape to play, as in part 1
+    Shape,
+    /// The response is how the round should end, as in part 2
+    Outcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoding {
+    pub interpretation: Interpretation,
+    /// What each response letter (`X`, `Y`, ...) stands for: a shape index, or an index
+    /// into [`Outcome::ALL`]
+    pub mapping: Vec<usize>,
+    pub score: u32,
+}
 
-                _ => {
-                    panic!("unexpected response")
+impl Decoding {
+    /// Spells out the mapping, e.g. `X=Rock Y=Paper Z=Scissors`
+    pub fn describe(&self, game: &Game) -> String {
+        self.mapping
+            .iter()
+            .enumerate()
+            .map(|(i, &meaning)| {
+                let letter = (b'X' + i as u8) as char;
+                match self.interpretation {
+                    Interpretation::Shape => format!("{}={}", letter, game.name(Shape(meaning))),
+                    Interpretation::Outcome => format!("{}={:?}", letter, Outcome::ALL[meaning]),
                 }
-            }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Every way of giving each of `k` letters a different one of `n` meanings
+fn arrangements(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+
+    arrangements(n, k - 1)
+        .into_iter()
+        .flat_map(|prefix| {
+            (0..n)
+                .filter(|meaning| !prefix.contains(meaning))
+                .map(|meaning| {
+                    let mut arrangement = prefix.clone();
+                    arrangement.push(meaning);
+                    arrangement
+                })
+                .collect::<Vec<_>>()
         })
-        .sum::<u32>();
+        .collect()
+}
+
+/// Scores the guide under every way of reading the response column: each assignment of the
+/// letters used to different shapes, and, for up to three letters, to different outcomes
+pub fn decode(game: &Game, input: &str) -> Vec<Decoding> {
+    // the guide is reduced to how often each round occurs, so every mapping is a cheap sum
+    let mut rounds: BTreeMap<(Shape, usize), u32> = BTreeMap::new();
+    for line in input.lines() {
+        *rounds.entry(parse_round(game, line)).or_insert(0) += 1;
+    }
+    let letters = rounds
+        .keys()
+        .map(|&(_, response)| response + 1)
+        .max()
+        .unwrap_or(0);
+    assert!(letters <= game.len(), "unexpected response");
+
+    let score = |interpretation: Interpretation, mapping: &[usize]| {
+        rounds
+            .iter()
+            .map(|(&(opponent, response), &count)| {
+                let me = match interpretation {
+                    Interpretation::Shape => Shape(mapping[response]),
+                    Interpretation::Outcome => game
+                        .shape_for(opponent, Outcome::ALL[mapping[response]])
+                        .unwrap(),
+                };
+                count * game.score(me, opponent)
+            })
+            .sum()
+    };
+
+    let mut decodings = vec![];
+    for mapping in arrangements(game.len(), letters) {
+        decodings.push(Decoding {
+            interpretation: Interpretation::Shape,
+            score: score(Interpretation::Shape, &mapping),
+            mapping,
+        });
+    }
+    if letters <= Outcome::ALL.len() && game.len() == Outcome::ALL.len() {
+        for mapping in arrangements(Outcome::ALL.len(), letters) {
+            decodings.push(Decoding {
+                interpretation: Interpretation::Outcome,
+                score: score(Interpretation::Outcome, &mapping),
+                mapping,
+            });
+        }
+    }
 
-    result
+    decodings
+}
+
+/// The decoding with the highest score, the first one found on ties
+pub fn best_decoding(game: &Game, input: &str) -> Option<Decoding> {
+    decode(game, input).into_iter().reduce(|best, decoding| {
+        if decoding.score > best.score {
+            decoding
+        } else {
+            best
+        }
+    })
+}
+
+/// Lists the score of the guide under every decoding, best first
+pub fn decoding_report(input: &str) -> String {
+    let game = Game::rock_paper_scissors();
+    let mut decodings = decode(&game, input);
+    decodings.sort_by_key(|decoding| Reverse(decoding.score));
+
+    decodings
+        .iter()
+        .map(|decoding| {
+            format!(
+                "{:>6}  {:<7} {}\n",
+                decoding.score,
+                format!("{:?}", decoding.interpretation),
+                decoding.describe(&game)
+            )
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -109,4 +391,80 @@ C Z";
         let result = process_part2(&INPUT);
         assert_eq!(result, 12);
     }
+
+    #[test]
+    fn cyclic_games_work() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let shape = |name| game.shape(name).unwrap();
+
+        assert_eq!(game.outcome(shape("Paper"), shape("Rock")), Outcome::Win);
+        assert_eq!(game.outcome(shape("Paper"), shape("Spock")), Outcome::Win);
+        assert_eq!(game.outcome(shape("Paper"), shape("Lizard")), Outcome::Loss);
+        assert_eq!(game.outcome(shape("Rock"), shape("Lizard")), Outcome::Win);
+        assert_eq!(
+            game.outcome(shape("Spock"), shape("Scissors")),
+            Outcome::Win
+        );
+        assert_eq!(game.score(shape("Scissors"), shape("Rock")), 5);
+        assert_eq!(
+            game.shape_for(shape("Rock"), Outcome::Win),
+            Some(shape("Paper"))
+        );
+
+        assert!(Game::cyclic(&["Rock", "Paper"], vec![1, 2], OutcomeScores::default()).is_err());
+    }
+
+    #[test]
+    fn invalid_relations_are_rejected() {
+        let scores = OutcomeScores::default();
+        let names = vec![String::from("Rock"), String::from("Paper")];
+
+        assert!(Game::new(
+            names.clone(),
+            vec![1, 2],
+            vec![vec![false, true], vec![true, false]],
+            scores
+        )
+        .is_err());
+        assert!(Game::new(
+            names,
+            vec![1, 2],
+            vec![vec![false, false], vec![true, false]],
+            scores
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn decoding_works() {
+        let game = Game::rock_paper_scissors();
+        let decodings = decode(&game, INPUT);
+
+        assert_eq!(decodings.len(), 12);
+        assert!(decodings.contains(&Decoding {
+            interpretation: Interpretation::Shape,
+            mapping: vec![0, 1, 2],
+            score: process_part1(INPUT),
+        }));
+        assert!(decodings.contains(&Decoding {
+            interpretation: Interpretation::Outcome,
+            mapping: vec![0, 1, 2],
+            score: process_part2(INPUT),
+        }));
+
+        let best = best_decoding(&game, INPUT).unwrap();
+        assert_eq!(best.score, 24);
+        assert_eq!(best.describe(&game), "X=Scissors Y=Paper Z=Rock");
+
+        // only the three letters in the guide are mapped, each to a different shape
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let decodings = decode(&game, INPUT);
+        assert_eq!(decodings.len(), 5 * 4 * 3);
+        assert!(decodings
+            .iter()
+            .all(|decoding| decoding.describe(&game).split(' ').count() == 3));
+        let best = best_decoding(&game, INPUT).unwrap();
+        assert!(best.describe(&game).starts_with("X="));
+        assert!(!best.describe(&game).contains('['));
+    }
 }
@@ -0,0 +1,294 @@
+//! Plays the strategy guide, or the best response to the opponent, against models of how the
+//! opponent picks their shapes.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{parse_round, Game, Interpretation, Outcome, Shape};
+
+/// Probability of each shape of a [`Game`], indexed like the shapes
+pub type Distribution = Vec<f64>;
+
+pub trait Opponent {
+    /// Probability of each shape being played next, given the rounds so far as `(mine, theirs)`
+    fn predict(&self, game: &Game, history: &[(Shape, Shape)]) -> Distribution;
+}
+
+/// Scales the weights to add up to 1, `None` when they're all zero
+fn normalize(mut weights: Vec<f64>) -> Option<Distribution> {
+    let total = weights.iter().sum::<f64>();
+    if total <= 0.0 {
+        return None;
+    }
+    weights.iter_mut().for_each(|weight| *weight /= total);
+    Some(weights)
+}
+
+/// Opponent column of the guide
+fn opponent_shapes(game: &Game, input: &str) -> Vec<Shape> {
+    input
+        .lines()
+        .map(|line| parse_round(game, line).0)
+        .collect()
+}
+
+/// Picks every round from the same distribution
+pub struct Fixed(pub Distribution);
+
+impl Fixed {
+    pub fn uniform(game: &Game) -> Fixed {
+        Fixed(normalize(vec![1.0; game.len()]).expect("a game has at least one shape"))
+    }
+
+    /// Plays each shape as often as the opponent does in the guide, `None` for an empty guide
+    pub fn from_guide(game: &Game, input: &str) -> Option<Fixed> {
+        let mut counts = vec![0.0; game.len()];
+        for shape in opponent_shapes(game, input) {
+            counts[shape.0] += 1.0;
+        }
+        normalize(counts).map(Fixed)
+    }
+}
+
+impl Opponent for Fixed {
+    fn predict(&self, _game: &Game, _history: &[(Shape, Shape)]) -> Distribution {
+        self.0.clone()
+    }
+}
+
+/// Expects the player to keep playing what they played before, and counters it: each shape
+/// that beats one of the player's past shapes is as likely as that shape was played
+pub struct FrequencyFollowing;
+
+impl Opponent for FrequencyFollowing {
+    fn predict(&self, game: &Game, history: &[(Shape, Shape)]) -> Distribution {
+        // every shape starts with one play so nothing is ruled out before the first round
+        let mut played = vec![1.0; game.len()];
+        for (mine, _) in history {
+            played[mine.0] += 1.0;
+        }
+
+        let mut counters = vec![0.0; game.len()];
+        for shape in game.shapes() {
+            let counter = game.shape_for(shape, Outcome::Win).unwrap();
+            counters[counter.0] += played[shape.0];
+        }
+        normalize(counters).expect("every shape has been played at least once")
+    }
+}
+
+/// Picks the next shape based on their previous one, with the transitions seen in the guide
+pub struct Markov {
+    /// Distribution of the first round
+    pub initial: Distribution,
+    /// `transitions[a]` is the distribution of the round after playing `a`
+    pub transitions: Vec<Distribution>,
+}
+
+impl Markov {
+    /// Learns the transitions of the guide, `None` for an empty guide
+    pub fn learn(game: &Game, input: &str) -> Option<Markov> {
+        let shapes = opponent_shapes(game, input);
+
+        // add-one smoothing, so transitions missing from the guide still happen now and then
+        let mut transitions = vec![vec![1.0; game.len()]; game.len()];
+        for pair in shapes.windows(2) {
+            transitions[pair[0].0][pair[1].0] += 1.0;
+        }
+
+        Some(Markov {
+            initial: Fixed::from_guide(game, input)?.0,
+            transitions: transitions
+                .into_iter()
+                .map(normalize)
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+impl Opponent for Markov {
+    fn predict(&self, _game: &Game, history: &[(Shape, Shape)]) -> Distribution {
+        match history.last() {
+            Some((_, theirs)) => self.transitions[theirs.0].clone(),
+            None => self.initial.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Plays the guide's rounds in order, read with the given interpretation, starting over
+    /// when it runs out
+    Guide(Interpretation),
+    /// Plays the shape with the best expected score against the opponent's next move
+    BestResponse,
+}
+
+/// Average score of playing `me` against an opponent picking from `distribution`
+pub fn expected_score(game: &Game, distribution: &Distribution, me: Shape) -> f64 {
+    game.shapes()
+        .map(|theirs| distribution[theirs.0] * game.score(me, theirs) as f64)
+        .sum()
+}
+
+/// The shape with the best expected score, the first one on ties
+pub fn best_response(game: &Game, distribution: &Distribution) -> Shape {
+    game.shapes()
+        .map(|me| (me, expected_score(game, distribution, me)))
+        .reduce(|best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .map(|(me, _)| me)
+        .expect("a game has at least one shape")
+}
+
+fn guide_shapes(game: &Game, input: &str, interpretation: Interpretation) -> Vec<Shape> {
+    input
+        .lines()
+        .map(|line| {
+            let (opponent, response) = parse_round(game, line);
+            match interpretation {
+                Interpretation::Shape => Shape(response),
+                Interpretation::Outcome => {
+                    game.shape_for(opponent, Outcome::ALL[response]).unwrap()
+                }
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub rounds: usize,
+    /// Average score per round expected from the opponent's distributions
+    pub expected: f64,
+    /// Average score per round actually sampled
+    pub simulated: f64,
+}
+
+/// Plays `rounds` rounds of `strategy` against `opponent`. The same `seed` plays the same rounds.
+/// `None` without rounds to average over, or when following an empty guide.
+pub fn simulate(
+    game: &Game,
+    guide: &str,
+    opponent: &dyn Opponent,
+    strategy: Strategy,
+    rounds: usize,
+    seed: u64,
+) -> Option<Summary> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let guide = match strategy {
+        Strategy::Guide(interpretation) => guide_shapes(game, guide, interpretation),
+        Strategy::BestResponse => vec![],
+    };
+    if rounds == 0 || (matches!(strategy, Strategy::Guide(_)) && guide.is_empty()) {
+        return None;
+    }
+
+    let mut history = Vec::with_capacity(rounds);
+    let mut expected = 0.0;
+    let mut simulated: u64 = 0;
+
+    for round in 0..rounds {
+        let distribution = opponent.predict(game, &history);
+        let me = match strategy {
+            Strategy::Guide(_) => guide[round % guide.len()],
+            Strategy::BestResponse => best_response(game, &distribution),
+        };
+
+        let mut sample = rng.gen::<f64>();
+        let theirs = game
+            .shapes()
+            .find(|shape| {
+                sample -= distribution[shape.0];
+                sample < 0.0
+            })
+            .unwrap_or(Shape(game.len() - 1));
+
+        expected += expected_score(game, &distribution, me);
+        simulated += game.score(me, theirs) as u64;
+        history.push((me, theirs));
+    }
+
+    Some(Summary {
+        rounds,
+        expected: expected / rounds as f64,
+        simulated: simulated as f64 / rounds as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "A Y
+B X
+C Z";
+
+    #[test]
+    fn fixed_works() {
+        let game = Game::rock_paper_scissors();
+
+        let always_rock = Fixed(vec![1.0, 0.0, 0.0]);
+        assert_eq!(best_response(&game, &always_rock.0), Shape(1));
+        let summary = simulate(&game, INPUT, &always_rock, Strategy::BestResponse, 100, 1).unwrap();
+        assert_eq!(summary.expected, 8.0);
+        assert_eq!(summary.simulated, 8.0);
+
+        // a shape scores its own value plus 3 on average against a uniform opponent
+        let uniform = Fixed::uniform(&game);
+        let summary = simulate(
+            &game,
+            INPUT,
+            &uniform,
+            Strategy::Guide(Interpretation::Shape),
+            3,
+            1,
+        )
+        .unwrap();
+        assert!((summary.expected - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn adaptive_opponents_work() {
+        let game = Game::rock_paper_scissors();
+
+        let markov = Markov::learn(&game, INPUT).unwrap();
+        assert_eq!(markov.initial, vec![1.0 / 3.0; 3]);
+        assert_eq!(markov.transitions[0], vec![0.25, 0.5, 0.25]);
+
+        // after a lot of rock, paper is the likeliest reply
+        let history = vec![(Shape(0), Shape(0)); 10];
+        let prediction = FrequencyFollowing.predict(&game, &history);
+        assert_eq!(best_response(&game, &prediction), Shape(2));
+
+        let summary = simulate(
+            &game,
+            INPUT,
+            &FrequencyFollowing,
+            Strategy::BestResponse,
+            1000,
+            7,
+        )
+        .unwrap();
+        assert!(summary.expected > 3.0);
+    }
+
+    #[test]
+    fn empty_guides_are_rejected() {
+        let game = Game::rock_paper_scissors();
+        let uniform = Fixed::uniform(&game);
+
+        assert!(Fixed::from_guide(&game, "").is_none());
+        assert!(Markov::learn(&game, "").is_none());
+        let strategy = Strategy::Guide(Interpretation::Shape);
+        assert_eq!(simulate(&game, "", &uniform, strategy, 10, 1), None);
+        assert_eq!(
+            simulate(&game, INPUT, &uniform, Strategy::BestResponse, 0, 1),
+            None
+        );
+    }
+}
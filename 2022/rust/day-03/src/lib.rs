@@ -1,58 +1,361 @@
-#![feature(iter_array_chunks)]
+use std::fmt::{self, Display, Write};
 
-use std::collections::HashMap;
+/// Priority of an item type: `a` to `z` are 1 to 26, `A` to `Z` are 27 to 52
+pub fn priority(item: char) -> Option<u32> {
+    match item {
+        'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+        _ => None,
+    }
+}
 
-pub fn process_part1(input: &str) -> usize {
-    let letter_scores = ('a'..='z')
-        .chain('A'..='Z')
-        .enumerate()
-        .map(|(idx, c)| (c, idx + 1))
-        .collect::<HashMap<char, usize>>();
+/// Item type of a priority, the reverse of [`priority`]
+pub fn item(priority: u32) -> Option<char> {
+    match priority {
+        1..=26 => char::from_u32('a' as u32 + priority - 1),
+        27..=52 => char::from_u32('A' as u32 + priority - 27),
+        _ => None,
+    }
+}
 
-    let result = input
-        .lines()
-        .map(|rucksack| {
-            let len = rucksack.len() / 2;
-            let compartment_1 = &rucksack[..len];
-            let compartment_2 = &rucksack[len..];
+/// Set of item types, with bit `priority - 1` standing for each type
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Items(u64);
+
+impl Items {
+    /// Every item type, used as the starting point of intersections
+    pub const ALL: Items = Items((1 << 52) - 1);
 
-            let common_item = compartment_1
+    /// Collects the item types in `items`, anything that isn't a letter is left out
+    pub fn parse(items: &str) -> Items {
+        Items(
+            items
                 .chars()
-                .find(|c| compartment_2.contains(*c))
-                .unwrap();
-            letter_scores.get(&common_item).unwrap()
+                .filter_map(priority)
+                .fold(0, |set, priority| set | 1 << (priority - 1)),
+        )
+    }
+
+    pub fn intersection(self, other: Items) -> Items {
+        Items(self.0 & other.0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Priorities of the item types, lowest first
+    pub fn priorities(self) -> impl Iterator<Item = u32> {
+        (1..=52).filter(move |priority| self.0 & 1 << (priority - 1) != 0)
+    }
+
+    /// The item types, in priority order
+    pub fn items(self) -> impl Iterator<Item = char> {
+        self.priorities().filter_map(item)
+    }
+
+    pub fn priority_sum(self) -> u32 {
+        self.priorities().sum()
+    }
+}
+
+impl FromIterator<Items> for Items {
+    /// Intersects all the sets
+    fn from_iter<I: IntoIterator<Item = Items>>(iter: I) -> Self {
+        iter.into_iter().fold(Items::ALL, Items::intersection)
+    }
+}
+
+/// Splits a rucksack into `compartments` parts of (nearly) equal size, counted in characters
+pub fn compartments(rucksack: &str, compartments: usize) -> Vec<&str> {
+    // byte offset of every character and of the end, so slices never cut a character
+    let boundaries = rucksack
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([rucksack.len()])
+        .collect::<Vec<_>>();
+    let len = boundaries.len() - 1;
+    (0..compartments)
+        .map(|i| {
+            &rucksack[boundaries[i * len / compartments]..boundaries[(i + 1) * len / compartments]]
         })
-        .sum::<usize>();
-    result
+        .collect()
 }
 
-pub fn process_part2(input: &str) -> usize {
-    let letter_scores = ('a'..='z')
-        .chain('A'..='Z')
+/// Item types found in every compartment of the rucksack
+pub fn shared_in_compartments(rucksack: &str, count: usize) -> Items {
+    compartments(rucksack, count)
+        .into_iter()
+        .map(Items::parse)
+        .collect()
+}
+
+/// Item types carried by every elf of each group of `group_size` consecutive rucksacks
+pub fn shared_in_groups(input: &str, group_size: usize) -> Vec<Items> {
+    let rucksacks = input.lines().collect::<Vec<_>>();
+    rucksacks
+        .chunks(group_size)
+        .map(|group| {
+            group
+                .iter()
+                .map(|rucksack| Items::parse(rucksack))
+                .collect()
+        })
+        .collect()
+}
+
+pub struct Params {
+    /// Compartments each rucksack is split into in part 1
+    pub compartments: usize,
+    /// Elves per group in part 2
+    pub group_size: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            compartments: 2,
+            group_size: 3,
+        }
+    }
+}
+
+impl Params {
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value
+            .parse::<usize>()
+            .map_err(|err| format!("{}: {}", key, err))?;
+        if value == 0 {
+            return Err(format!("{}: has to be at least 1", key));
+        }
+        match key {
+            "compartments" => self.compartments = value,
+            "group_size" => self.group_size = value,
+            _ => return Err(format!("unknown parameter `{}`", key)),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The rucksack can't be split into compartments of equal size
+    UnevenLength { len: usize, compartments: usize },
+    /// Nothing is packed in every compartment
+    NoSharedItem,
+    /// More than one item type is packed in every compartment
+    SharedItems(Items),
+    /// A character that isn't an item type, at a 1-based column
+    InvalidCharacter { column: usize, character: char },
+    /// The group starting on this line has fewer than `group_size` elves
+    IncompleteGroup { size: usize },
+    /// No item type is carried by the whole group starting on this line
+    NoBadge,
+    /// More than one item type is carried by the whole group starting on this line
+    AmbiguousBadge(Items),
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::UnevenLength { len, compartments } => write!(
+                f,
+                "{} items don't split into {} equal compartments",
+                len, compartments
+            ),
+            Problem::NoSharedItem => write!(f, "no item is in every compartment"),
+            Problem::SharedItems(items) => write!(
+                f,
+                "{} items are in every compartment: {}",
+                items.len(),
+                items.items().collect::<String>()
+            ),
+            Problem::InvalidCharacter { column, character } => {
+                write!(f, "column {}: {:?} is not an item", column, character)
+            }
+            Problem::IncompleteGroup { size } => write!(f, "incomplete group of {}", size),
+            Problem::NoBadge => write!(f, "group has no badge"),
+            Problem::AmbiguousBadge(items) => write!(
+                f,
+                "group badge is ambiguous: {}",
+                items.items().collect::<String>()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based line of the rucksack, or of the first rucksack of the group
+    pub line: usize,
+    pub problem: Problem,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.problem)
+    }
+}
+
+fn validate_rucksack(rucksack: &str, compartments: usize) -> Vec<Problem> {
+    let mut problems = rucksack
+        .chars()
         .enumerate()
-        .map(|(idx, c)| (c, idx + 1))
-        .collect::<HashMap<char, usize>>();
+        .filter(|(_, character)| priority(*character).is_none())
+        .map(|(i, character)| Problem::InvalidCharacter {
+            column: i + 1,
+            character,
+        })
+        .collect::<Vec<_>>();
 
-    let result = input
-        .lines()
-        .array_chunks::<3>()
-        .map(|[a, b, c]| {
-            let common_item = a
-                .chars()
-                .find(|a_char| b.contains(*a_char) && c.contains(*a_char))
-                .unwrap();
+    // compartments are counted in characters, so invalid ones still leave them well defined
+    let len = rucksack.chars().count();
+    if !len.is_multiple_of(compartments) {
+        problems.push(Problem::UnevenLength { len, compartments });
+        return problems;
+    }
+
+    let shared = shared_in_compartments(rucksack, compartments);
+    match shared.len() {
+        0 => problems.push(Problem::NoSharedItem),
+        1 => {}
+        _ => problems.push(Problem::SharedItems(shared)),
+    }
+    problems
+}
+
+/// Everything wrong with the input, group by group, each group's rucksacks before the group itself
+pub fn validate(input: &str, params: &Params) -> Vec<Diagnostic> {
+    let rucksacks = input.lines().collect::<Vec<_>>();
+    let mut diagnostics = vec![];
+
+    for (group_index, group) in rucksacks.chunks(params.group_size).enumerate() {
+        let first_line = group_index * params.group_size + 1;
+
+        for (i, rucksack) in group.iter().enumerate() {
+            diagnostics.extend(
+                validate_rucksack(rucksack, params.compartments)
+                    .into_iter()
+                    .map(|problem| Diagnostic {
+                        line: first_line + i,
+                        problem,
+                    }),
+            );
+        }
+
+        let badges = group
+            .iter()
+            .map(|rucksack| Items::parse(rucksack))
+            .collect::<Items>();
+        let mut group_problem = |problem| {
+            diagnostics.push(Diagnostic {
+                line: first_line,
+                problem,
+            })
+        };
+        if group.len() < params.group_size {
+            group_problem(Problem::IncompleteGroup { size: group.len() });
+        }
+        match badges.len() {
+            0 => group_problem(Problem::NoBadge),
+            1 => {}
+            _ => group_problem(Problem::AmbiguousBadge(badges)),
+        }
+    }
+
+    diagnostics
+}
 
-            letter_scores.get(&common_item).unwrap()
+/// Lists the problems of the input, followed by a count of the rucksacks and groups affected
+pub fn validation_report(input: &str) -> String {
+    let params = Params::default();
+    let diagnostics = validate(input, &params);
+    let mut report = String::new();
+
+    for diagnostic in diagnostics.iter() {
+        writeln!(report, "{}", diagnostic).unwrap();
+    }
+
+    let is_group_problem = |problem: &Problem| {
+        matches!(
+            problem,
+            Problem::IncompleteGroup { .. } | Problem::NoBadge | Problem::AmbiguousBadge(_)
+        )
+    };
+    let mut rucksack_lines = diagnostics
+        .iter()
+        .filter(|diagnostic| !is_group_problem(&diagnostic.problem))
+        .map(|diagnostic| diagnostic.line)
+        .collect::<Vec<_>>();
+    rucksack_lines.dedup();
+    let mut group_lines = diagnostics
+        .iter()
+        .filter(|diagnostic| is_group_problem(&diagnostic.problem))
+        .map(|diagnostic| diagnostic.line)
+        .collect::<Vec<_>>();
+    group_lines.dedup();
+
+    let rucksacks = input.lines().count();
+    if !diagnostics.is_empty() {
+        writeln!(report).unwrap();
+    }
+    writeln!(
+        report,
+        "rucksacks: {} ({} invalid)",
+        rucksacks,
+        rucksack_lines.len()
+    )
+    .unwrap();
+    writeln!(
+        report,
+        "groups:    {} ({} invalid)",
+        rucksacks.div_ceil(params.group_size),
+        group_lines.len()
+    )
+    .unwrap();
+
+    report
+}
+
+pub fn process_part1(input: &str) -> usize {
+    process_part1_with(input, &Params::default())
+}
+
+pub fn process_part1_with(input: &str, params: &Params) -> usize {
+    input
+        .lines()
+        .map(|rucksack| {
+            shared_in_compartments(rucksack, params.compartments).priority_sum() as usize
         })
-        .sum::<usize>();
+        .sum::<usize>()
+}
+
+pub fn process_part2(input: &str) -> usize {
+    process_part2_with(input, &Params::default())
+}
 
-    result
+pub fn process_part2_with(input: &str, params: &Params) -> usize {
+    shared_in_groups(input, params.group_size)
+        .into_iter()
+        .map(|badges| badges.priority_sum() as usize)
+        .sum::<usize>()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+
     #[test]
     fn part1_works() {
         const INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
@@ -76,4 +379,71 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
         let result = process_part2(&INPUT);
         assert_eq!(result, 70);
     }
+
+    #[test]
+    fn items_work() {
+        assert_eq!(priority('p'), Some(16));
+        assert_eq!(priority('L'), Some(38));
+        assert_eq!(item(38), Some('L'));
+
+        let shared = Items::parse("abcXY").intersection(Items::parse("cdYZ"));
+        assert_eq!(shared.items().collect::<String>(), "cY");
+        assert_eq!(shared.priority_sum(), 3 + 51);
+
+        assert_eq!(compartments("aébc", 2), vec!["aé", "bc"]);
+        assert_eq!(process_part1("aéba\nÿaéa"), 2);
+    }
+
+    #[test]
+    fn params_work() {
+        let badges = shared_in_groups("abc\nbcd\ncde\nAc", 2);
+        assert_eq!(badges.len(), 2);
+        assert_eq!(badges[0].items().collect::<String>(), "bc");
+        assert_eq!(badges[1].items().collect::<String>(), "c");
+
+        let mut params = Params::default();
+        params.set("compartments", "3").unwrap();
+        assert_eq!(process_part1_with("aabaca\nxyz", &params), 1);
+        assert!(params.set("group_size", "0").is_err());
+        assert!(params.set("knots", "3").is_err());
+    }
+
+    #[test]
+    fn validation_works() {
+        let diagnostics = validate(INPUT, &Params::default());
+        assert!(diagnostics.is_empty());
+
+        let input = "abBab\naBcaBx\naB1B\nxx";
+        let diagnostics = validate(input, &Params::default())
+            .into_iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            vec![
+                "line 1: 5 items don't split into 2 equal compartments",
+                "line 2: 2 items are in every compartment: aB",
+                "line 3: column 3: '1' is not an item",
+                "line 1: group badge is ambiguous: aB",
+                "line 4: incomplete group of 1",
+            ]
+        );
+
+        let report = validation_report(input);
+        assert!(report.ends_with("rucksacks: 4 (3 invalid)\ngroups:    2 (2 invalid)\n"));
+
+        assert_eq!(
+            validate("abéa\nacéa\nadéa", &Params::default()),
+            vec![1, 2, 3]
+                .into_iter()
+                .map(|line| Diagnostic {
+                    line,
+                    problem: Problem::InvalidCharacter {
+                        column: 3,
+                        character: 'é'
+                    }
+                })
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,160 @@
+//! Queries over the sections covered by a whole list of assignments, built with a sweep line.
+
+use crate::interval::Interval;
+
+/// How many intervals cover each section, as maximal runs of sections with the same depth
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    /// Runs covered at least once, in order. Adjacent runs have different depths.
+    runs: Vec<(Interval, usize)>,
+}
+
+impl Coverage {
+    pub fn new(intervals: &[Interval]) -> Coverage {
+        // positions are u128 so the run after `u64::MAX` can still end
+        let mut events = intervals
+            .iter()
+            .flat_map(|interval| {
+                [
+                    (interval.start() as u128, 1),
+                    (interval.end() as u128 + 1, -1),
+                ]
+            })
+            .collect::<Vec<(u128, i64)>>();
+        events.sort_unstable();
+
+        let mut runs: Vec<(Interval, usize)> = vec![];
+        let mut depth = 0;
+        let mut events = events.into_iter().peekable();
+        while let Some((position, change)) = events.next() {
+            depth += change;
+            // apply every event at this position before looking at the next run
+            if events.peek().is_some_and(|(next, _)| *next == position) {
+                continue;
+            }
+
+            let Some(&(next, _)) = events.peek() else {
+                break;
+            };
+            if depth == 0 {
+                continue;
+            }
+            let run = Interval::new(position as u64, (next - 1) as u64).unwrap();
+            match runs.last_mut() {
+                Some((last, last_depth))
+                    if *last_depth == depth as usize && last.end() as u128 + 1 == position =>
+                {
+                    *last = last.union(&run).unwrap();
+                }
+                _ => runs.push((run, depth as usize)),
+            }
+        }
+
+        Coverage { runs }
+    }
+
+    /// Every run of sections with its depth, in order
+    pub fn runs(&self) -> &[(Interval, usize)] {
+        &self.runs
+    }
+
+    /// The sections covered by at least `k` intervals, merged into as few intervals as possible
+    pub fn at_least(&self, k: usize) -> Vec<Interval> {
+        let mut merged: Vec<Interval> = vec![];
+        for (run, _) in self.runs.iter().filter(|(_, depth)| *depth >= k.max(1)) {
+            match merged.last_mut().and_then(|last| last.union(run)) {
+                Some(union) => *merged.last_mut().unwrap() = union,
+                None => merged.push(*run),
+            }
+        }
+        merged
+    }
+
+    /// The sections covered by any interval
+    pub fn merged(&self) -> Vec<Interval> {
+        self.at_least(1)
+    }
+
+    /// The sections between the first and last covered ones that nothing covers
+    pub fn gaps(&self) -> Vec<Interval> {
+        self.merged()
+            .windows(2)
+            .map(|pair| Interval::new(pair[0].end() + 1, pair[1].start() - 1).unwrap())
+            .collect()
+    }
+
+    /// The highest depth and the first run reaching it, `None` without intervals
+    pub fn max_depth(&self) -> Option<(usize, Interval)> {
+        self.runs
+            .iter()
+            .map(|(run, depth)| (*depth, *run))
+            .reduce(|best, candidate| {
+                if candidate.0 > best.0 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+}
+
+/// Indices of as few intervals as possible that together cover every section any of them covers
+pub fn minimal_cover(intervals: &[Interval]) -> Vec<usize> {
+    let mut order = (0..intervals.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| intervals[i].start());
+
+    let mut cover = vec![];
+    let mut next = 0;
+    for component in Coverage::new(intervals).merged() {
+        // the first section of the component that isn't covered yet
+        let mut uncovered = component.start() as u128;
+        while uncovered <= component.end() as u128 {
+            let mut best: Option<usize> = None;
+            while next < order.len() && intervals[order[next]].start() as u128 <= uncovered {
+                let candidate = order[next];
+                if best.is_none_or(|best| intervals[candidate].end() > intervals[best].end()) {
+                    best = Some(candidate);
+                }
+                next += 1;
+            }
+            // the merged component guarantees some interval starts at or before `uncovered`
+            // and reaches it, the ones skipped here end before it
+            let best = best.expect("coverage leaves no gap inside a component");
+            cover.push(best);
+            uncovered = intervals[best].end() as u128 + 1;
+        }
+    }
+    cover
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intervals(ranges: &str) -> Vec<Interval> {
+        ranges
+            .split(',')
+            .map(|range| range.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn coverage_works() {
+        let coverage = Coverage::new(&intervals("2-4,6-8,3-7,10-12,11-11"));
+
+        assert_eq!(coverage.merged(), intervals("2-8,10-12"));
+        assert_eq!(coverage.gaps(), intervals("9-9"));
+        assert_eq!(coverage.at_least(2), intervals("3-4,6-7,11-11"));
+        assert_eq!(coverage.max_depth(), Some((2, "3-4".parse().unwrap())));
+        assert_eq!(Coverage::new(&[]).max_depth(), None);
+    }
+
+    #[test]
+    fn minimal_cover_works() {
+        let ranges = intervals("2-4,3-5,2-3,5-8,4-6,10-10");
+        assert_eq!(minimal_cover(&ranges), vec![0, 3, 5]);
+
+        let ranges = intervals(&format!("0-10,5-{}", u64::MAX));
+        assert_eq!(minimal_cover(&ranges), vec![0, 1]);
+    }
+}
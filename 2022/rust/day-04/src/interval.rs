@@ -0,0 +1,155 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Inclusive range of section IDs, never empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    start: u64,
+    end: u64,
+}
+
+impl Interval {
+    /// `None` if `start` comes after `end`
+    pub fn new(start: u64, end: u64) -> Option<Interval> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Number of sections, which doesn't fit in a `u64` for `0-u64::MAX`
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u128 {
+        (self.end - self.start) as u128 + 1
+    }
+
+    pub fn contains(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn contains_section(&self, section: u64) -> bool {
+        self.start <= section && section <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The sections of both, `None` if they leave a gap between them
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        let touching = self.overlaps(other)
+            || self.end.checked_add(1) == Some(other.start)
+            || other.end.checked_add(1) == Some(self.start);
+        touching.then(|| Interval {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntervalError {
+    /// There's no `-` between the sections
+    MissingDash(String),
+    /// A section isn't a number
+    InvalidSection(String),
+    /// The first section comes after the last
+    Reversed { start: u64, end: u64 },
+}
+
+impl Display for IntervalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntervalError::MissingDash(range) => write!(f, "`{}` is not a range", range),
+            IntervalError::InvalidSection(section) => {
+                write!(f, "`{}` is not a section", section)
+            }
+            IntervalError::Reversed { start, end } => {
+                write!(f, "range {}-{} ends before it starts", start, end)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IntervalError {}
+
+impl FromStr for Interval {
+    type Err = IntervalError;
+
+    /// Parses `start-end`
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let (start, end) = range
+            .split_once('-')
+            .ok_or_else(|| IntervalError::MissingDash(range.to_string()))?;
+        let section = |section: &str| {
+            section
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| IntervalError::InvalidSection(section.to_string()))
+        };
+        let (start, end) = (section(start)?, section(end)?);
+        Interval::new(start, end).ok_or(IntervalError::Reversed { start, end })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(range: &str) -> Interval {
+        range.parse().unwrap()
+    }
+
+    #[test]
+    fn algebra_works() {
+        let a = interval("2-6");
+        let b = interval("4-8");
+
+        assert_eq!(a.len(), 5);
+        assert!(!a.contains(&b));
+        assert!(interval("2-8").contains(&interval("3-7")));
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&interval("7-9")));
+        assert_eq!(a.intersection(&b), Some(interval("4-6")));
+        assert_eq!(a.intersection(&interval("7-9")), None);
+        assert_eq!(a.union(&interval("7-9")), Some(interval("2-9")));
+        assert_eq!(a.union(&interval("8-9")), None);
+        assert_eq!(
+            interval(&format!("0-{}", u64::MAX)).len(),
+            u64::MAX as u128 + 1
+        );
+    }
+
+    #[test]
+    fn parsing_works() {
+        assert_eq!(
+            "5".parse::<Interval>(),
+            Err(IntervalError::MissingDash(String::from("5")))
+        );
+        assert_eq!(
+            "5-x".parse::<Interval>(),
+            Err(IntervalError::InvalidSection(String::from("x")))
+        );
+        assert_eq!(
+            "7-5".parse::<Interval>(),
+            Err(IntervalError::Reversed { start: 7, end: 5 })
+        );
+    }
+}
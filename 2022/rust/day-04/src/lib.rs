@@ -1,68 +1,135 @@
-pub fn process_part1(input: &str) -> String {
-    let result = input
-        .lines()
-        .map(|pair_data| {
-            let pair_data = pair_data
-                .split(",")
-                .map(|assigned_sections| {
-                    let parsed_vector = assigned_sections
-                        .split("-")
-                        .map(|section| section.parse::<i32>().unwrap())
-                        .collect::<Vec<_>>();
-                    parsed_vector[0]..=parsed_vector[1]
-                })
-                .collect::<Vec<_>>();
-
-            (pair_data[0].clone(), pair_data[1].clone())
+pub mod coverage;
+pub mod interval;
+
+use std::fmt::{self, Display};
+
+use interval::{Interval, IntervalError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line given to [`parse_pairs`] doesn't hold exactly two ranges
+    RangeCount {
+        line: usize,
+        count: usize,
+    },
+    InvalidRange {
+        line: usize,
+        error: IntervalError,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::RangeCount { line, count } => {
+                write!(f, "line {}: expected 2 ranges, found {}", line, count)
+            }
+            ParseError::InvalidRange { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The assignments of the elves on one line, in input order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group(pub Vec<Interval>);
+
+impl Group {
+    /// Whether some assignment contains another one, equal assignments contain each other
+    pub fn any_contains_another(&self) -> bool {
+        let mut ranges = self.0.clone();
+        // widest first among equal starts, so a range is contained in an earlier one exactly
+        // when it ends no later than the furthest end seen so far
+        ranges.sort_by_key(|range| (range.start(), std::cmp::Reverse(range.end())));
+        ranges
+            .iter()
+            .scan(None, |furthest: &mut Option<u64>, range| {
+                let contained = furthest.is_some_and(|end| range.end() <= end);
+                *furthest = (*furthest).max(Some(range.end()));
+                Some(contained)
+            })
+            .any(|contained| contained)
+    }
+
+    /// The sections every assignment covers, `None` if there are none or the group is empty
+    pub fn intersection(&self) -> Option<Interval> {
+        let (first, rest) = self.0.split_first()?;
+        rest.iter()
+            .try_fold(*first, |common, range| common.intersection(range))
+    }
+
+    /// Whether every two assignments overlap, which for ranges means they all share a section
+    pub fn all_overlap(&self) -> bool {
+        self.intersection().is_some()
+    }
+}
+
+fn parse_line(line: usize, group: &str) -> Result<Group, ParseError> {
+    group
+        .split(',')
+        .map(|range| {
+            range
+                .parse::<Interval>()
+                .map_err(|error| ParseError::InvalidRange { line, error })
         })
-        .filter(|(range_a, range_b)| {
-            let a_contains_b = range_a
-                .clone()
-                .into_iter()
-                .all(|num| range_b.contains(&num));
-
-            let b_contains_a = range_b
-                .clone()
-                .into_iter()
-                .all(|num| range_a.contains(&num));
-
-            a_contains_b || b_contains_a
+        .collect::<Result<Vec<_>, _>>()
+        .map(Group)
+}
+
+/// Parses one line of comma separated `a-b` ranges per group of elves
+pub fn parse_groups(input: &str) -> Result<Vec<Group>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, group)| parse_line(i + 1, group))
+        .collect()
+}
+
+/// Parses one `a-b,c-d` line per pair of elves
+pub fn parse_pairs(input: &str) -> Result<Vec<(Interval, Interval)>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, pair)| {
+            let line = i + 1;
+            let Group(ranges) = parse_line(line, pair)?;
+            match ranges[..] {
+                [a, b] => Ok((a, b)),
+                _ => Err(ParseError::RangeCount {
+                    line,
+                    count: ranges.len(),
+                }),
+            }
         })
+        .collect()
+}
+
+/// Every elf's assignment in input order
+pub fn assignments(groups: &[Group]) -> Vec<Interval> {
+    groups
+        .iter()
+        .flat_map(|group| group.0.iter().copied())
+        .collect()
+}
+
+fn parse(input: &str) -> Vec<Group> {
+    parse_groups(input).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub fn process_part1(input: &str) -> String {
+    let result = parse(input)
+        .iter()
+        .filter(|group| group.any_contains_another())
         .count();
 
     result.to_string()
 }
 
 pub fn process_part2(input: &str) -> String {
-    let result = input
-        .lines()
-        .map(|pair_data| {
-            let pair_data = pair_data
-                .split(",")
-                .map(|assigned_sections| {
-                    let parsed_vector = assigned_sections
-                        .split("-")
-                        .map(|section| section.parse::<i32>().unwrap())
-                        .collect::<Vec<_>>();
-                    parsed_vector[0]..=parsed_vector[1]
-                })
-                .collect::<Vec<_>>();
-
-            (pair_data[0].clone(), pair_data[1].clone())
-        })
-        .filter(|(range_a, range_b)| {
-            let a_overlaps_b = range_a
-                .clone()
-                .into_iter()
-                .any(|num| range_b.contains(&num));
-
-            let b_overlaps_a = range_b
-                .clone()
-                .into_iter()
-                .any(|num| range_a.contains(&num));
-
-            a_overlaps_b || b_overlaps_a
-        })
+    let result = parse(input)
+        .iter()
+        .filter(|group| group.all_overlap())
         .count();
 
     result.to_string()
@@ -90,4 +157,45 @@ mod tests {
         let result = process_part2(&INPUT);
         assert_eq!(result, "4");
     }
+
+    #[test]
+    fn coverage_works() {
+        let elves = assignments(&parse(INPUT));
+        assert_eq!(elves.len(), 12);
+        let coverage = coverage::Coverage::new(&elves);
+
+        assert_eq!(coverage.merged(), vec![Interval::new(2, 9).unwrap()]);
+        assert!(coverage.gaps().is_empty());
+        assert_eq!(
+            coverage.max_depth(),
+            Some((8, Interval::new(6, 6).unwrap()))
+        );
+        assert_eq!(coverage::minimal_cover(&elves), vec![6, 5]);
+    }
+
+    #[test]
+    fn groups_work() {
+        let groups = parse_groups("2-8,3-7,4-9\n1-3,2-5,3-6\n1-2,2-3,3-4\n5-5").unwrap();
+
+        assert!(groups[0].any_contains_another());
+        assert!(!groups[1].any_contains_another());
+        assert_eq!(groups[0].intersection(), Interval::new(4, 7));
+        assert_eq!(groups[1].intersection(), Interval::new(3, 3));
+        assert!(!groups[2].all_overlap());
+        assert!(groups[3].all_overlap());
+        assert!(!groups[3].any_contains_another());
+        assert_eq!(process_part1("2-8,3-7,4-9\n1-3,2-5,3-6\n6-6,6-6"), "2");
+    }
+
+    #[test]
+    fn parse_errors_have_lines() {
+        assert_eq!(
+            parse_pairs("1-2,3-4\n1-2"),
+            Err(ParseError::RangeCount { line: 2, count: 1 })
+        );
+        assert_eq!(
+            parse_pairs("1-2,4-3").unwrap_err().to_string(),
+            "line 1: range 4-3 ends before it starts"
+        );
+    }
 }
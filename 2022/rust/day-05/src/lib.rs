@@ -1,69 +1,177 @@
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::{
-        complete::{self, alpha1, digit1, multispace1, newline},
-        streaming::space1,
-    },
-    multi::{many1, separated_list1},
-    sequence::{delimited, preceded},
-    IResult,
-};
+pub mod plan;
+pub mod replay;
 
-fn parse_crate(input: &str) -> IResult<&str, Option<&str>> {
-    let (input, parsed_value) = alt((
-        tag("   "),
-        delimited(complete::char('['), alpha1, complete::char(']')),
-    ))(input)?;
+use std::fmt::{self, Display};
 
-    let result = match parsed_value {
-        "   " => None,
-        value => Some(value),
-    };
+use nom::{bytes::complete::tag, character::complete, combinator::all_consuming, IResult};
 
-    Ok((input, result))
-}
+/// Crates of each stack, bottom first
+pub type Stacks<'a> = Vec<Vec<&'a str>>;
 
-fn line(input: &str) -> IResult<&str, Vec<Option<&str>>> {
-    let (input, result) = separated_list1(tag(" "), parse_crate)(input)?;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// There's no line of stack numbers under the drawing
+    MissingBaseline,
+    /// The stack numbers aren't `1 2 3 ...` in order
+    InvalidBaseline { line: usize },
+    /// Something other than a `[...]` crate at a 1-based column
+    InvalidCrate { line: usize, column: usize },
+    /// A crate or stack number at a 1-based column that doesn't line up with the columns of the
+    /// stacks, or with a different width than the other crates
+    Misaligned { line: usize, column: usize },
+    /// A line under the drawing that isn't a move
+    InvalidMove { line: usize },
+}
 
-    Ok((input, result))
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingBaseline => write!(f, "the drawing has no stack numbers"),
+            ParseError::InvalidBaseline { line } => {
+                write!(f, "line {}: stack numbers should go 1, 2, 3, ...", line)
+            }
+            ParseError::InvalidCrate { line, column } => {
+                write!(f, "line {}, column {}: expected a crate", line, column)
+            }
+            ParseError::Misaligned { line, column } => write!(
+                f,
+                "line {}, column {}: not aligned with the stacks",
+                line, column
+            ),
+            ParseError::InvalidMove { line } => write!(f, "line {}: expected a move", line),
+        }
+    }
 }
 
-fn crates(input: &str) -> IResult<&str, (Vec<Vec<&str>>, Vec<Move>)> {
-    let (input, crates_horizontal) = separated_list1(newline, line)(input)?;
-    let (input, _) = newline(input)?;
-    let (input, _numbers) = many1(preceded(space1, digit1))(input)?;
-    let (input, _) = multispace1(input)?;
+impl std::error::Error for ParseError {}
 
-    let (input, moves) = separated_list1(newline, move_crate)(input)?;
+/// A `[...]` crate in a row of the drawing
+struct Token<'a> {
+    /// 0-based character column of the `[`
+    column: usize,
+    /// Characters from `[` to `]`
+    width: usize,
+    label: &'a str,
+}
 
-    // construct the vertical crate stacks
-    let mut crates_vertical: Vec<Vec<Option<&str>>> = vec![];
+fn tokens(row: &str, line: usize) -> Result<Vec<Token<'_>>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = row.char_indices().enumerate();
 
-    for _ in 0..=crates_horizontal.len() {
-        crates_vertical.push(vec![]);
+    while let Some((column, (start, c))) = chars.next() {
+        match c {
+            ' ' => continue,
+            '[' => {
+                let (end_column, (end, _)) = chars.by_ref().find(|(_, (_, c))| *c == ']').ok_or(
+                    ParseError::InvalidCrate {
+                        line,
+                        column: column + 1,
+                    },
+                )?;
+                let label = row[start + 1..end].trim();
+                if label.is_empty() {
+                    return Err(ParseError::InvalidCrate {
+                        line,
+                        column: column + 1,
+                    });
+                }
+                tokens.push(Token {
+                    column,
+                    width: end_column - column + 1,
+                    label,
+                });
+            }
+            _ => {
+                return Err(ParseError::InvalidCrate {
+                    line,
+                    column: column + 1,
+                })
+            }
+        }
     }
 
-    for vec in crates_horizontal.iter().rev() {
-        for (i, crate_) in vec.iter().enumerate() {
-            crates_vertical[i].push(*crate_);
+    Ok(tokens)
+}
+
+/// Parses the drawing on `lines`, the last one being the stack numbers. Every column is as wide
+/// as its crates, plus a space between columns. Trailing spaces may be left out.
+fn drawing<'a>(lines: &[(usize, &'a str)]) -> Result<Stacks<'a>, ParseError> {
+    let ((baseline_line, baseline), rows) =
+        lines.split_last().ok_or(ParseError::MissingBaseline)?;
+
+    // each number with the 0-based character column it starts at
+    let mut numbers: Vec<(usize, String)> = vec![];
+    let mut previous = ' ';
+    for (column, c) in baseline.chars().enumerate() {
+        match (previous.is_whitespace(), c.is_whitespace()) {
+            (true, false) => numbers.push((column, c.to_string())),
+            (false, false) => numbers.last_mut().unwrap().1.push(c),
+            _ => {}
         }
+        previous = c;
+    }
+    let numbers_in_order = numbers
+        .iter()
+        .enumerate()
+        .all(|(i, (_, number))| number.parse::<usize>() == Ok(i + 1));
+    if numbers.is_empty() || !numbers_in_order {
+        return Err(ParseError::InvalidBaseline {
+            line: *baseline_line,
+        });
     }
 
-    // filter out the None values
-    let final_crates = crates_vertical
+    let rows = rows
+        .iter()
+        .map(|(line, row)| Ok((*line, tokens(row, *line)?)))
+        .collect::<Result<Vec<_>, ParseError>>()?;
+    // the first crate sets the width of every column
+    let width = rows
         .iter()
-        .map(|vec| vec.iter().filter_map(|c| *c).collect())
-        .collect::<Vec<Vec<&str>>>();
+        .flat_map(|(_, tokens)| tokens.first())
+        .map(|token| token.width)
+        .next()
+        .unwrap_or(3);
 
-    Ok((input, (final_crates, moves)))
+    for (i, (column, _)) in numbers.iter().enumerate() {
+        if column / (width + 1) != i {
+            return Err(ParseError::Misaligned {
+                line: *baseline_line,
+                column: column + 1,
+            });
+        }
+    }
+
+    let mut stacks: Stacks = vec![vec![]; numbers.len()];
+    for (line, tokens) in rows.iter().rev() {
+        for token in tokens {
+            let stack = token.column / (width + 1);
+            if token.width != width || token.column % (width + 1) != 0 || stack >= stacks.len() {
+                return Err(ParseError::Misaligned {
+                    line: *line,
+                    column: token.column + 1,
+                });
+            }
+            stacks[stack].push(token.label);
+        }
+    }
+
+    Ok(stacks)
 }
 
-struct Move {
-    number: u32,
-    from: u32,
-    to: u32,
+/// `move number from from to to`, with the stacks counted from 1 like the puzzle does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub number: u32,
+    pub from: u32,
+    pub to: u32,
+    /// Line of the move in the input, starting at 1
+    pub line: usize,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.number, self.from, self.to)
+    }
 }
 
 fn move_crate(input: &str) -> IResult<&str, Move> {
@@ -78,61 +186,239 @@ fn move_crate(input: &str) -> IResult<&str, Move> {
         input,
         Move {
             number,
-            from: from - 1,
-            to: to - 1,
+            from,
+            to,
+            line: 0,
         },
     ))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, (mut crates, moves)) = crates(input).unwrap();
-
-    for Move { number, from, to } in moves.iter() {
-        let len = crates[*from as usize].len();
-        for crate_ in crates[*from as usize]
-            .drain((len - (*number as usize))..)
-            .rev()
-            .collect::<Vec<&str>>()
-            .iter()
-        {
-            crates[*to as usize].push(crate_);
-        }
-    }
+/// Parses just the drawing of the stacks, as produced by [`render`]
+pub fn parse_drawing(input: &str) -> Result<Stacks<'_>, ParseError> {
+    let lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect::<Vec<_>>();
+    drawing(&lines)
+}
 
-    let top_crates: String = crates
+/// Draws the stacks the way the puzzle does, with the stack numbers on the last line. Every
+/// column is as wide as the widest crate, or the widest stack number.
+pub fn render(stacks: &Stacks) -> String {
+    let width = stacks
         .iter()
-        .map(|c| match c.iter().last() {
-            Some(c) => c,
-            None => "",
+        .flatten()
+        .map(|crate_| crate_.chars().count() + 2)
+        .chain([3, stacks.len().to_string().len()])
+        .max()
+        .unwrap();
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut lines = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(crate_) => format!("[{:^w$}]", crate_, w = width - 2),
+                    None => " ".repeat(width),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
         })
-        .collect();
+        .collect::<Vec<_>>();
+    lines.push(
+        (1..=stacks.len())
+            .map(|number| format!("{:^w$}", number, w = width))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
 
-    top_crates
+    lines.join("\n")
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_, (mut crates, moves)) = crates(input).unwrap();
-
-    for Move { number, from, to } in moves.iter() {
-        let len = crates[*from as usize].len();
-        for crate_ in crates[*from as usize]
-            .drain((len - (*number as usize))..)
-            .collect::<Vec<&str>>()
-            .iter()
-        {
-            crates[*to as usize].push(crate_);
+/// Parses the drawing of the stacks and the moves below it, separated by a blank line
+pub fn parse(input: &str) -> Result<(Stacks<'_>, Vec<Move>), ParseError> {
+    let lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .collect::<Vec<_>>();
+    let split = lines
+        .iter()
+        .position(|(_, line)| line.trim().is_empty())
+        .unwrap_or(lines.len());
+    let (drawing_lines, move_lines) = lines.split_at(split);
+
+    let stacks = drawing(drawing_lines)?;
+    let moves = move_lines
+        .iter()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|&(line, move_)| {
+            let (_, move_) = all_consuming(move_crate)(move_.trim_end())
+                .map_err(|_| ParseError::InvalidMove { line })?;
+            Ok(Move { line, ..move_ })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((stacks, moves))
+}
+
+pub trait Crane {
+    /// Reorders the crates taken off a stack, given bottom first, into the order they end up
+    /// on the other stack
+    fn arrange(&self, crates: &mut [&str]);
+}
+
+/// Lifts one crate at a time, which reverses them
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn arrange(&self, crates: &mut [&str]) {
+        crates.reverse();
+    }
+}
+
+/// Lifts all the crates at once, which keeps their order
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn arrange(&self, _crates: &mut [&str]) {}
+}
+
+/// Lifts at most `size` crates at a time off the top, flipping each batch if `reversed`
+pub struct Batched {
+    pub size: usize,
+    pub reversed: bool,
+}
+
+impl Crane for Batched {
+    fn arrange(&self, crates: &mut [&str]) {
+        // the top batch lands first, so the batches end up in reverse order
+        crates.reverse();
+        for batch in crates.chunks_mut(self.size.max(1)) {
+            if !self.reversed {
+                batch.reverse();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    /// The move names a stack that isn't in the drawing
+    UnknownStack {
+        line: usize,
+        stack: u32,
+        stacks: usize,
+    },
+    /// The move takes more crates than the stack holds
+    NotEnoughCrates {
+        line: usize,
+        stack: u32,
+        height: usize,
+        number: u32,
+    },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::UnknownStack {
+                line,
+                stack,
+                stacks,
+            } => write!(
+                f,
+                "line {}: there is no stack {}, only 1 to {}",
+                line, stack, stacks
+            ),
+            MoveError::NotEnoughCrates {
+                line,
+                stack,
+                height,
+                number,
+            } => write!(
+                f,
+                "line {}: can't move {} crates off stack {}, it only has {}",
+                line, number, stack, height
+            ),
         }
     }
+}
+
+impl std::error::Error for MoveError {}
+
+fn stack_index(stacks: &Stacks, stack: u32, line: usize) -> Result<usize, MoveError> {
+    match stack as usize {
+        index @ 1.. if index <= stacks.len() => Ok(index - 1),
+        _ => Err(MoveError::UnknownStack {
+            line,
+            stack,
+            stacks: stacks.len(),
+        }),
+    }
+}
+
+/// Runs a single move, leaving the stacks untouched if it isn't possible
+pub fn apply(stacks: &mut Stacks, move_: &Move, crane: &dyn Crane) -> Result<(), MoveError> {
+    let from = stack_index(stacks, move_.from, move_.line)?;
+    let to = stack_index(stacks, move_.to, move_.line)?;
+
+    let height = stacks[from].len();
+    let remaining =
+        height
+            .checked_sub(move_.number as usize)
+            .ok_or(MoveError::NotEnoughCrates {
+                line: move_.line,
+                stack: move_.from,
+                height,
+                number: move_.number,
+            })?;
+
+    let mut lifted = stacks[from].split_off(remaining);
+    crane.arrange(&mut lifted);
+    stacks[to].extend(lifted);
+    Ok(())
+}
+
+/// Runs the moves on the stacks with the given crane, stopping at the first impossible one
+pub fn simulate<'a>(
+    mut stacks: Stacks<'a>,
+    moves: &[Move],
+    crane: &dyn Crane,
+) -> Result<Stacks<'a>, MoveError> {
+    for move_ in moves.iter() {
+        apply(&mut stacks, move_, crane)?;
+    }
+    Ok(stacks)
+}
 
-    let top_crates: String = crates
+/// The crate on top of each stack, empty stacks are skipped
+pub fn top_crates(stacks: &Stacks) -> String {
+    stacks
         .iter()
         .map(|c| match c.iter().last() {
             Some(c) => c,
             None => "",
         })
-        .collect();
+        .collect()
+}
 
-    top_crates
+fn solve(input: &str, crane: &dyn Crane) -> String {
+    let (crates, moves) = parse(input).unwrap_or_else(|err| panic!("{}", err));
+    let crates = simulate(crates, &moves, crane).unwrap_or_else(|err| panic!("{}", err));
+    top_crates(&crates)
+}
+
+pub fn process_part1(input: &str) -> String {
+    solve(input, &CrateMover9000)
+}
+
+pub fn process_part2(input: &str) -> String {
+    solve(input, &CrateMover9001)
 }
 
 #[cfg(test)]
@@ -160,4 +446,138 @@ move 1 from 1 to 2";
         let result = process_part2(&INPUT);
         assert_eq!(result, "MCD");
     }
+
+    #[test]
+    fn cranes_work() {
+        let (crates, moves) = parse(INPUT).unwrap();
+        let batched = |size, reversed| {
+            top_crates(&simulate(crates.clone(), &moves, &Batched { size, reversed }).unwrap())
+        };
+
+        assert_eq!(batched(1, false), "CMZ");
+        assert_eq!(batched(3, false), "MCD");
+        assert_eq!(batched(3, true), "CMZ");
+
+        let mut lifted = vec!["A", "B", "C", "D", "E"];
+        Batched {
+            size: 2,
+            reversed: false,
+        }
+        .arrange(&mut lifted);
+        assert_eq!(lifted, vec!["D", "E", "B", "C", "A"]);
+    }
+
+    #[test]
+    fn render_round_trips() {
+        let drawing = INPUT.split("\n\n").next().unwrap();
+        let (crates, moves) = parse(INPUT).unwrap();
+
+        assert_eq!(render(&crates), drawing);
+        assert_eq!(parse_drawing(drawing), Ok(crates.clone()));
+        assert_eq!(moves[0].to_string(), "move 1 from 2 to 1");
+
+        let crates = simulate(crates, &moves, &CrateMover9000).unwrap();
+        assert_eq!(
+            render(&crates),
+            "        [Z]
+        [N]
+        [D]
+[C] [M] [P]
+ 1   2   3 "
+        );
+        assert_eq!(parse_drawing(&render(&crates)), Ok(crates));
+    }
+
+    #[test]
+    fn invalid_moves_are_rejected() {
+        let (crates, moves) = parse(INPUT).unwrap();
+        assert_eq!(moves[1].line, 7);
+
+        let mut stacks = crates.clone();
+        let too_many = Move {
+            number: 3,
+            from: 3,
+            to: 1,
+            line: 6,
+        };
+        assert_eq!(
+            apply(&mut stacks, &too_many, &CrateMover9000),
+            Err(MoveError::NotEnoughCrates {
+                line: 6,
+                stack: 3,
+                height: 1,
+                number: 3
+            })
+        );
+        assert_eq!(stacks, crates);
+
+        let input = INPUT.replace("to 3", "to 0");
+        let (crates, moves) = parse(&input).unwrap();
+        assert_eq!(
+            simulate(crates, &moves, &CrateMover9000)
+                .unwrap_err()
+                .to_string(),
+            "line 7: there is no stack 0, only 1 to 3"
+        );
+    }
+
+    #[test]
+    fn wide_drawings_parse() {
+        let stacks: Stacks = (0..12)
+            .map(|i| {
+                if i % 3 == 0 {
+                    vec!["A", "B"]
+                } else {
+                    vec!["C"]
+                }
+            })
+            .collect();
+        let drawing = render(&stacks);
+        assert!(drawing.ends_with(" 10  11  12 "));
+        assert_eq!(parse_drawing(&drawing), Ok(stacks));
+
+        let stacks: Stacks = vec![vec!["Z", "XY"], vec![], vec!["ABC"]];
+        let drawing = render(&stacks);
+        assert_eq!(
+            drawing,
+            "[XY ]            \n[ Z ]       [ABC]\n  1     2     3  "
+        );
+        assert_eq!(parse_drawing(&drawing), Ok(stacks));
+    }
+
+    #[test]
+    fn ragged_drawings_parse() {
+        let trimmed = INPUT
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(parse(&trimmed), parse(INPUT));
+    }
+
+    #[test]
+    fn bad_drawings_are_rejected() {
+        assert_eq!(
+            parse_drawing("[A]  [B]\n 1   2"),
+            Err(ParseError::Misaligned { line: 1, column: 6 })
+        );
+        assert_eq!(
+            parse_drawing("[A] [BC]\n 1   2"),
+            Err(ParseError::Misaligned { line: 1, column: 5 })
+        );
+        assert_eq!(
+            parse_drawing("[A] B\n 1   2"),
+            Err(ParseError::InvalidCrate { line: 1, column: 5 })
+        );
+        assert_eq!(
+            parse_drawing("[A]\n 1   3"),
+            Err(ParseError::InvalidBaseline { line: 2 })
+        );
+        assert_eq!(
+            parse(&INPUT.replace("move 2", "mvoe 2"))
+                .unwrap_err()
+                .to_string(),
+            "line 8: expected a move"
+        );
+    }
 }
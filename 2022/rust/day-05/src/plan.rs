@@ -0,0 +1,230 @@
+//! Searches for a short list of moves that turns one arrangement of the stacks into another.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::{self, Display},
+};
+
+use crate::{apply, render, Crane, Move, Stacks};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    /// The target has a different number of stacks
+    StackCount { start: usize, target: usize },
+    /// The target doesn't hold the same crates, so no moves can reach it
+    DifferentCrates,
+    /// No plan was found after looking at `explored` arrangements
+    LimitReached { explored: usize },
+    /// Every arrangement the moves can reach was tried without finding the target
+    Unreachable,
+}
+
+impl Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::StackCount { start, target } => {
+                write!(f, "can't go from {} stacks to {} stacks", start, target)
+            }
+            PlanError::DifferentCrates => write!(f, "the target holds different crates"),
+            PlanError::LimitReached { explored } => {
+                write!(f, "no plan found in {} arrangements", explored)
+            }
+            PlanError::Unreachable => write!(f, "no moves lead to the target"),
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+fn sorted_crates<'a>(stacks: &Stacks<'a>) -> Vec<&'a str> {
+    let mut crates = stacks.iter().flatten().copied().collect::<Vec<_>>();
+    crates.sort_unstable();
+    crates
+}
+
+/// Every stack that differs from the target needs at least one move, and a move touches two
+/// stacks, so this never overestimates the moves left
+fn estimate(stacks: &Stacks, target: &Stacks) -> usize {
+    let differing = stacks
+        .iter()
+        .zip(target.iter())
+        .filter(|(stack, target)| stack != target)
+        .count();
+    differing.div_ceil(2)
+}
+
+/// Finds a plan with as few moves as possible with an A* search, giving up after looking at
+/// `limit` arrangements. The moves are numbered by their position in the plan, from 1.
+pub fn plan(
+    start: &Stacks,
+    target: &Stacks,
+    crane: &dyn Crane,
+    limit: usize,
+) -> Result<Vec<Move>, PlanError> {
+    if start.len() != target.len() {
+        return Err(PlanError::StackCount {
+            start: start.len(),
+            target: target.len(),
+        });
+    }
+    if sorted_crates(start) != sorted_crates(target) {
+        return Err(PlanError::DifferentCrates);
+    }
+
+    // every arrangement seen, with the arrangement and move it was reached from
+    let mut arrangements = vec![(start.clone(), None::<(usize, Move)>)];
+    let mut best = HashMap::from([(start.clone(), 0)]);
+    let mut queue = BinaryHeap::from([Reverse((estimate(start, target), 0, 0))]);
+    let mut explored = 0;
+
+    while let Some(Reverse((_, moves, id))) = queue.pop() {
+        let stacks = arrangements[id].0.clone();
+        if best[&stacks] < moves {
+            continue;
+        }
+
+        if &stacks == target {
+            let mut plan = vec![];
+            let mut current = id;
+            while let Some((previous, move_)) = arrangements[current].1 {
+                plan.push(move_);
+                current = previous;
+            }
+            plan.reverse();
+            for (i, move_) in plan.iter_mut().enumerate() {
+                move_.line = i + 1;
+            }
+            return Ok(plan);
+        }
+
+        explored += 1;
+        if explored > limit {
+            return Err(PlanError::LimitReached { explored: limit });
+        }
+
+        for from in 0..stacks.len() {
+            for to in (0..stacks.len()).filter(|&to| to != from) {
+                for number in 1..=stacks[from].len() {
+                    let move_ = Move {
+                        number: number as u32,
+                        from: from as u32 + 1,
+                        to: to as u32 + 1,
+                        line: 0,
+                    };
+                    let mut next = stacks.clone();
+                    apply(&mut next, &move_, crane).expect("moves are within the stacks");
+
+                    if best.get(&next).is_some_and(|&seen| seen <= moves + 1) {
+                        continue;
+                    }
+                    best.insert(next.clone(), moves + 1);
+                    queue.push(Reverse((
+                        moves + 1 + estimate(&next, target),
+                        moves + 1,
+                        arrangements.len(),
+                    )));
+                    arrangements.push((next, Some((id, move_))));
+                }
+            }
+        }
+    }
+
+    // with two or more stacks any arrangement of the same crates can be reached, with a single
+    // stack no move is possible at all
+    Err(PlanError::Unreachable)
+}
+
+/// A puzzle input with the drawing of `start` followed by the plan, which the solver reads back
+pub fn plan_input(start: &Stacks, plan: &[Move]) -> String {
+    let moves = plan
+        .iter()
+        .map(Move::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("{}\n\n{}", render(start), moves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, parse_drawing, simulate, CrateMover9000, CrateMover9001};
+
+    const START: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3 ";
+
+    #[test]
+    fn finds_short_plans() {
+        let start = parse_drawing(START).unwrap();
+        let target =
+            parse_drawing("        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 ")
+                .unwrap();
+
+        let found = plan(&start, &target, &CrateMover9001, 10_000).unwrap();
+        assert!(found.len() <= 4);
+
+        let input = plan_input(&start, &found);
+        let (stacks, moves) = parse(&input).unwrap();
+        assert_eq!(
+            moves.iter().map(Move::to_string).collect::<Vec<_>>(),
+            found.iter().map(Move::to_string).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            simulate(stacks, &moves, &CrateMover9001),
+            Ok(target.clone())
+        );
+
+        // one move of the whole stack reverses it with the 9000 and keeps it with the 9001
+        let target =
+            parse_drawing("        [D]\n        [C]\n[N]     [M]\n[Z]     [P]\n 1   2   3 ")
+                .unwrap();
+        let plan9000 = plan(&start, &target, &CrateMover9000, 10_000).unwrap();
+        let plan9001 = plan(&start, &target, &CrateMover9001, 10_000).unwrap();
+        assert_eq!(plan9001.len(), 1);
+        assert!(plan9000.len() > 1);
+        assert_eq!(simulate(start, &plan9000, &CrateMover9000), Ok(target));
+    }
+
+    #[test]
+    fn impossible_targets_are_rejected() {
+        let start = parse_drawing(START).unwrap();
+
+        assert_eq!(
+            plan(
+                &start,
+                &parse_drawing("[A]\n 1 ").unwrap(),
+                &CrateMover9000,
+                100
+            ),
+            Err(PlanError::StackCount {
+                start: 3,
+                target: 1
+            })
+        );
+        assert_eq!(
+            plan(
+                &start,
+                &parse_drawing("[A]\n 1   2   3").unwrap(),
+                &CrateMover9000,
+                100
+            ),
+            Err(PlanError::DifferentCrates)
+        );
+        let reversed = start.iter().rev().cloned().collect::<Stacks>();
+        assert_eq!(
+            plan(&start, &reversed, &CrateMover9000, 1),
+            Err(PlanError::LimitReached { explored: 1 })
+        );
+        assert_eq!(
+            plan(
+                &parse_drawing("[A]\n[B]\n 1 ").unwrap(),
+                &parse_drawing("[B]\n[A]\n 1 ").unwrap(),
+                &CrateMover9000,
+                100
+            ),
+            Err(PlanError::Unreachable)
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Steps through the moves one at a time, keeping every state on the way so they can be undone
+//! and looked at again.
+
+use crate::{apply, Crane, Move, MoveError, Stacks};
+
+/// Where a crate is, with both counted from 1 and heights from the bottom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub stack: u32,
+    pub height: usize,
+}
+
+pub struct Replay<'a, 'c> {
+    moves: Vec<Move>,
+    crane: &'c dyn Crane,
+    /// `states[k]` holds the stacks after the first `k` moves, as far as they've been replayed
+    states: Vec<Stacks<'a>>,
+    /// `moved[k]` holds the crates moved by move `k + 1`, in the order they landed
+    moved: Vec<Vec<&'a str>>,
+    position: usize,
+}
+
+impl<'a, 'c> Replay<'a, 'c> {
+    pub fn new(stacks: Stacks<'a>, moves: Vec<Move>, crane: &'c dyn Crane) -> Replay<'a, 'c> {
+        Replay {
+            moves,
+            crane,
+            states: vec![stacks],
+            moved: vec![],
+            position: 0,
+        }
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Number of moves done so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn stacks(&self) -> &Stacks<'a> {
+        &self.states[self.position]
+    }
+
+    /// Runs the next move and returns the crates it moved, `None` when all the moves are done
+    pub fn step_forward(&mut self) -> Result<Option<&[&'a str]>, MoveError> {
+        if self.position == self.moves.len() {
+            return Ok(None);
+        }
+
+        if self.position + 1 == self.states.len() {
+            let move_ = self.moves[self.position];
+            let mut stacks = self.states[self.position].clone();
+            apply(&mut stacks, &move_, self.crane)?;
+
+            let to = &stacks[move_.to as usize - 1];
+            self.moved
+                .push(to[to.len() - move_.number as usize..].to_vec());
+            self.states.push(stacks);
+        }
+
+        self.position += 1;
+        Ok(Some(&self.moved[self.position - 1]))
+    }
+
+    /// Undoes the last move and returns the crates it had moved, `None` at the start
+    pub fn step_back(&mut self) -> Option<&[&'a str]> {
+        self.position = self.position.checked_sub(1)?;
+        Some(&self.moved[self.position])
+    }
+
+    /// Goes to the state after the first `k` moves, or after all of them if there are fewer.
+    /// Stays put if one of the moves on the way fails.
+    pub fn jump(&mut self, k: usize) -> Result<(), MoveError> {
+        let k = k.min(self.moves.len());
+        let position = self.position;
+        while self.states.len() <= k {
+            self.position = self.states.len() - 1;
+            if let Err(err) = self.step_forward() {
+                self.position = position;
+                return Err(err);
+            }
+        }
+        self.position = k;
+        Ok(())
+    }
+
+    /// The crates moved by each move replayed so far, including undone ones
+    pub fn history(&self) -> impl Iterator<Item = (&Move, &[&'a str])> {
+        self.moves.iter().zip(self.moved.iter().map(Vec::as_slice))
+    }
+
+    /// The stacks after the first `k` moves
+    pub fn stacks_after(&mut self, k: usize) -> Result<&Stacks<'a>, MoveError> {
+        let position = self.position;
+        self.jump(k)?;
+        self.position = position;
+        Ok(&self.states[k.min(self.moves.len())])
+    }
+
+    /// Everywhere a crate with the given label was after the first `k` moves, as labels needn't
+    /// be unique
+    pub fn locate(&mut self, label: &str, k: usize) -> Result<Vec<Location>, MoveError> {
+        let stacks = self.stacks_after(k)?;
+        Ok(stacks
+            .iter()
+            .enumerate()
+            .flat_map(|(i, stack)| {
+                stack
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, crate_)| **crate_ == label)
+                    .map(move |(height, _)| Location {
+                        stack: i as u32 + 1,
+                        height: height + 1,
+                    })
+            })
+            .collect())
+    }
+
+    /// The first move that takes crates off or puts crates on the stack, counted from 1
+    pub fn first_touching(&self, stack: u32) -> Option<&Move> {
+        self.moves
+            .iter()
+            .find(|move_| move_.from == stack || move_.to == stack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, render, CrateMover9000};
+
+    const INPUT: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+    #[test]
+    fn steps_both_ways() {
+        let (stacks, moves) = parse(INPUT).unwrap();
+        let mut replay = Replay::new(stacks.clone(), moves, &CrateMover9000);
+
+        assert_eq!(replay.step_forward(), Ok(Some(&["D"][..])));
+        assert_eq!(replay.step_forward(), Ok(Some(&["D", "N", "Z"][..])));
+        assert_eq!(replay.step_back(), Some(&["D", "N", "Z"][..]));
+        assert_eq!(replay.position(), 1);
+        assert_eq!(replay.step_back(), Some(&["D"][..]));
+        assert_eq!(replay.step_back(), None);
+        assert_eq!(replay.stacks(), &stacks);
+
+        replay.jump(10).unwrap();
+        assert_eq!(replay.position(), 4);
+        assert_eq!(replay.step_forward(), Ok(None));
+        assert_eq!(
+            render(replay.stacks()),
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 "
+        );
+        assert_eq!(replay.history().count(), 4);
+    }
+
+    #[test]
+    fn answers_queries() {
+        let (stacks, moves) = parse(INPUT).unwrap();
+        let mut replay = Replay::new(stacks, moves, &CrateMover9000);
+
+        assert_eq!(
+            replay.locate("Z", 2),
+            Ok(vec![Location {
+                stack: 3,
+                height: 4
+            }])
+        );
+        assert_eq!(replay.position(), 0);
+        assert_eq!(replay.locate("X", 1), Ok(vec![]));
+        assert_eq!(replay.first_touching(3).map(|move_| move_.line), Some(7));
+        assert_eq!(replay.first_touching(4), None);
+
+        let input = INPUT.replace("move 2", "move 5");
+        let (stacks, moves) = parse(&input).unwrap();
+        let mut replay = Replay::new(stacks, moves, &CrateMover9000);
+        replay.jump(1).unwrap();
+        assert!(replay.jump(4).is_err());
+        assert_eq!(replay.position(), 1);
+        assert!(replay.jump(2).is_ok());
+    }
+}
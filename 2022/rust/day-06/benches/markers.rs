@@ -0,0 +1,53 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day_06::first_marker;
+
+/// The detector `first_marker` replaced, rescanning every window
+fn rescanning_marker(input: &str, window: usize) -> Option<usize> {
+    let input_vec = input.chars().collect::<Vec<char>>();
+
+    for idx in window..=input_vec.len() {
+        let sequence = &input_vec.as_slice()[idx - window..idx];
+        if !(1..window).any(|i| sequence[i..].contains(&sequence[i - 1])) {
+            return Some(idx);
+        }
+    }
+    None
+}
+
+/// A stream of `len` letters without a marker of `window` letters until the very end
+fn stream(len: usize, window: usize) -> String {
+    let letters = b"abcdefghijklmnopqrstuvwxyz";
+    let mut stream = (0..len - window)
+        .map(|i| letters[i % (window - 1)] as char)
+        .collect::<String>();
+    stream.extend(letters[..window].iter().map(|&letter| letter as char).rev());
+    stream
+}
+
+fn markers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("markers");
+    group.sample_size(10);
+
+    for window in [4, 14, 26] {
+        let stream = stream(4 << 20, window);
+        group.throughput(Throughput::Bytes(stream.len() as u64));
+        assert_eq!(
+            first_marker(stream.as_bytes(), window),
+            rescanning_marker(&stream, window)
+        );
+
+        group.bench_with_input(BenchmarkId::new("linear", window), &stream, |b, stream| {
+            b.iter(|| first_marker(stream.as_bytes(), window))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("rescanning", window),
+            &stream,
+            |b, stream| b.iter(|| rescanning_marker(stream, window)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, markers);
+criterion_main!(benches);
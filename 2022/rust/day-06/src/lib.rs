@@ -1,41 +1,224 @@
-pub fn process_part1(input: &str) -> String {
-    let input_vec = input.chars().collect::<Vec<char>>();
-    let mut index = 0;
+use std::{
+    fmt::{self, Display},
+    io::{self, BufReader, Read},
+};
 
-    let window = 4;
+pub struct Params {
+    /// Distinct characters needed for a start-of-packet marker
+    pub packet_window: usize,
+    /// Distinct characters needed for a start-of-message marker
+    pub message_window: usize,
+}
 
-    for idx in 0..input_vec.len() {
-        if idx < window {
-            continue;
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            packet_window: 4,
+            message_window: 14,
         }
+    }
+}
 
-        let sequence = &input_vec.as_slice()[idx - window..idx];
-        if !(1..window).any(|i| sequence[i..].contains(&sequence[i - 1])) {
-            index = idx;
-            break;
+impl Params {
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value
+            .parse::<usize>()
+            .map_err(|err| format!("{}: {}", key, err))?;
+        if value == 0 {
+            return Err(format!("{}: has to be at least 1", key));
         }
+        match key {
+            "packet_window" => self.packet_window = value,
+            "message_window" => self.message_window = value,
+            _ => return Err(format!("unknown parameter `{}`", key)),
+        }
+        Ok(())
     }
-    index.to_string()
 }
 
-pub fn process_part2(input: &str) -> String {
-    let input_vec = input.chars().collect::<Vec<char>>();
-    let mut index = 0;
+/// The longest run of different bytes ending at some point of a stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    /// Number of bytes read so far, the run ends with the last of them
+    pub end: usize,
+    pub len: usize,
+}
+
+impl Run {
+    pub fn start(&self) -> usize {
+        self.end - self.len
+    }
+}
+
+/// Follows a stream one byte at a time, keeping track of the run of different bytes at its end
+pub struct Detector {
+    last_seen: [Option<usize>; 256],
+    /// Start of the current run
+    start: usize,
+    read: usize,
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Detector {
+            last_seen: [None; 256],
+            start: 0,
+            read: 0,
+        }
+    }
+}
 
-    let window = 14;
+impl Detector {
+    pub fn push(&mut self, byte: u8) -> Run {
+        if let Some(seen) = self.last_seen[byte as usize] {
+            self.start = self.start.max(seen + 1);
+        }
+        self.last_seen[byte as usize] = Some(self.read);
+        self.read += 1;
 
-    for idx in 0..input_vec.len() {
-        if idx < window {
-            continue;
+        Run {
+            end: self.read,
+            len: self.read - self.start,
         }
+    }
+}
 
-        let sequence = &input_vec.as_slice()[idx - window..idx];
-        if !(1..window).any(|i| sequence[i..].contains(&sequence[i - 1])) {
-            index = idx;
+/// The run ending at each byte of the stream
+pub fn runs(stream: &[u8]) -> impl Iterator<Item = Run> + '_ {
+    let mut detector = Detector::default();
+    stream.iter().map(move |&byte| detector.push(byte))
+}
+
+/// The run ending at each byte read from `reader`, which is buffered
+pub fn read_runs<R: Read>(reader: R) -> impl Iterator<Item = io::Result<Run>> {
+    let mut detector = Detector::default();
+    BufReader::new(reader)
+        .bytes()
+        .map(move |byte| byte.map(|byte| detector.push(byte)))
+}
+
+/// Number of bytes read when the last `window` of them are all different, in a single pass
+pub fn first_marker(stream: &[u8], window: usize) -> Option<usize> {
+    if window == 0 {
+        return Some(0);
+    }
+    runs(stream)
+        .find(|run| run.len >= window)
+        .map(|run| run.end)
+}
+
+/// Every point at which the last `window` bytes are all different, overlapping markers included
+pub fn markers(stream: &[u8], window: usize) -> impl Iterator<Item = usize> + '_ {
+    runs(stream)
+        .filter(move |run| run.len >= window)
+        .map(|run| run.end)
+}
+
+/// The longest run of different bytes, the first one on ties
+pub fn longest_run(runs: impl IntoIterator<Item = Run>) -> Option<Run> {
+    runs.into_iter()
+        .reduce(|longest, run| if run.len > longest.len { run } else { longest })
+}
+
+/// The first marker of each window size, reading the runs only until all of them are found
+pub fn first_markers(runs: impl IntoIterator<Item = Run>, windows: &[usize]) -> Vec<Option<usize>> {
+    let mut found = windows
+        .iter()
+        .map(|&window| (window == 0).then_some(0))
+        .collect::<Vec<_>>();
+
+    for run in runs {
+        if found.iter().all(Option::is_some) {
             break;
         }
+        for (marker, &window) in found.iter_mut().zip(windows) {
+            if marker.is_none() && run.len >= window {
+                *marker = Some(run.end);
+            }
+        }
+    }
+    found
+}
+
+/// [`first_markers`] over a stream read from `reader`
+pub fn read_first_markers<R: Read>(reader: R, windows: &[usize]) -> io::Result<Vec<Option<usize>>> {
+    let mut error = None;
+    let runs = read_runs(reader).map_while(|run| run.map_err(|err| error = Some(err)).ok());
+    let found = first_markers(runs, windows);
+    match error {
+        Some(err) => Err(err),
+        None => Ok(found),
     }
-    index.to_string()
+}
+
+/// A datastream without any `window` different characters in a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoMarker {
+    /// Line of the datastream, starting at 1
+    pub line: usize,
+    pub window: usize,
+}
+
+impl Display for NoMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: no {} different characters in a row",
+            self.line, self.window
+        )
+    }
+}
+
+impl std::error::Error for NoMarker {}
+
+/// The first marker of each datastream, one per non-blank line
+pub fn stream_markers(input: &str, window: usize) -> Vec<Result<usize, NoMarker>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, stream)| (i + 1, stream.trim_end()))
+        .filter(|(_, stream)| !stream.is_empty())
+        .map(|(line, stream)| {
+            first_marker(stream.as_bytes(), window).ok_or(NoMarker { line, window })
+        })
+        .collect()
+}
+
+/// The marker of each datastream on its own line, panics listing the ones without a marker
+fn find_marker(input: &str, window: usize) -> String {
+    let (markers, missing): (Vec<_>, Vec<_>) = stream_markers(input, window)
+        .into_iter()
+        .partition(Result::is_ok);
+
+    if !missing.is_empty() {
+        let missing = missing
+            .into_iter()
+            .map(|err| err.unwrap_err().to_string())
+            .collect::<Vec<_>>();
+        panic!("{}", missing.join("; "));
+    }
+
+    markers
+        .into_iter()
+        .map(|marker| marker.unwrap().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn process_part1(input: &str) -> String {
+    process_part1_with(input, &Params::default())
+}
+
+pub fn process_part1_with(input: &str, params: &Params) -> String {
+    find_marker(input, params.packet_window)
+}
+
+pub fn process_part2(input: &str) -> String {
+    process_part2_with(input, &Params::default())
+}
+
+pub fn process_part2_with(input: &str, params: &Params) -> String {
+    find_marker(input, params.message_window)
 }
 
 #[cfg(test)]
@@ -55,4 +238,74 @@ mod tests {
         let result = process_part2(&INPUT);
         assert_eq!(result, "19");
     }
+
+    #[test]
+    fn params_work() {
+        let mut params = Params::default();
+        params.set("packet_window", "5").unwrap();
+        params.set("message_window", "6").unwrap();
+
+        assert_eq!(process_part1_with(INPUT, &params), "8");
+        assert_eq!(process_part2_with(INPUT, &params), "9");
+        assert!(params.set("window", "5").is_err());
+        assert!(params.set("packet_window", "0").is_err());
+    }
+
+    #[test]
+    fn first_marker_works() {
+        let examples = [
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+        for (stream, packet, message) in examples {
+            assert_eq!(first_marker(stream.as_bytes(), 4), Some(packet));
+            assert_eq!(first_marker(stream.as_bytes(), 14), Some(message));
+        }
+
+        assert_eq!(first_marker(b"abcd", 4), Some(4));
+        assert_eq!(first_marker(b"abca", 4), None);
+        assert_eq!(first_marker(b"", 1), None);
+        assert_eq!(first_marker(b"aab", 0), Some(0));
+        assert_eq!(first_marker(b"aab", 1), Some(1));
+    }
+
+    #[test]
+    fn analysis_works() {
+        let stream = b"abcabcdab";
+
+        assert_eq!(
+            markers(stream, 3).collect::<Vec<_>>(),
+            vec![3, 4, 5, 6, 7, 8, 9]
+        );
+        assert_eq!(markers(stream, 4).collect::<Vec<_>>(), vec![7, 8, 9]);
+        assert_eq!(longest_run(runs(stream)), Some(Run { end: 7, len: 4 }));
+        assert_eq!(longest_run(runs(b"")), None);
+        assert_eq!(
+            first_markers(runs(INPUT.as_bytes()), &[14, 4, 30]),
+            vec![Some(19), Some(7), None]
+        );
+        assert_eq!(
+            read_first_markers(INPUT.as_bytes(), &[4, 14]).unwrap(),
+            vec![Some(7), Some(19)]
+        );
+    }
+
+    #[test]
+    fn multiple_streams_work() {
+        let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb\r\n\nbvwbjplbgvbhsrlpgdmjqwftvncz\naaaa\n";
+
+        assert_eq!(
+            stream_markers(input, 4),
+            vec![Ok(7), Ok(5), Err(NoMarker { line: 4, window: 4 })]
+        );
+        assert_eq!(process_part2(&input.replace("aaaa", "")), "19\n23");
+    }
+
+    #[test]
+    #[should_panic(expected = "line 1: no 4 different characters in a row")]
+    fn missing_marker_panics() {
+        process_part1("abcabc");
+    }
 }
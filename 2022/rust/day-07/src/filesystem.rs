@@ -0,0 +1,246 @@
+//! The directory tree explored in the terminal transcript.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+/// Index of a file or directory in its [`Filesystem`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    File {
+        size: u32,
+    },
+    /// Children by name, so they're listed in alphabetical order
+    Dir {
+        children: BTreeMap<String, NodeId>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Empty for the root directory
+    pub name: String,
+    /// `None` for the root directory
+    pub parent: Option<NodeId>,
+    pub kind: Kind,
+}
+
+impl Node {
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, Kind::Dir { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filesystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for Filesystem {
+    fn default() -> Self {
+        Filesystem {
+            nodes: vec![Node {
+                name: String::new(),
+                parent: None,
+                kind: Kind::Dir {
+                    children: BTreeMap::new(),
+                },
+            }],
+        }
+    }
+}
+
+impl Filesystem {
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    fn add(&mut self, parent: NodeId, name: &str, kind: Kind) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        let Kind::Dir { children } = &mut self.nodes[parent.0].kind else {
+            panic!("`{}` is a file", self.path(parent));
+        };
+        if let Some(&existing) = children.get(name) {
+            return existing;
+        }
+
+        children.insert(name.to_string(), id);
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+        });
+        id
+    }
+
+    /// Adds a directory to `parent`, or returns the one already there with that name
+    pub fn add_dir(&mut self, parent: NodeId, name: &str) -> NodeId {
+        self.add(
+            parent,
+            name,
+            Kind::Dir {
+                children: BTreeMap::new(),
+            },
+        )
+    }
+
+    /// Adds a file to `parent`, or returns whatever is already there with that name
+    pub fn add_file(&mut self, parent: NodeId, name: &str, size: u32) -> NodeId {
+        self.add(parent, name, Kind::File { size })
+    }
+
+    /// The files and directories right inside `id`, by name
+    pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let children = match &self.node(id).kind {
+            Kind::Dir { children } => Some(children.values().copied()),
+            Kind::File { .. } => None,
+        };
+        children.into_iter().flatten()
+    }
+
+    /// Looks up an absolute path like `/a/e`
+    pub fn lookup(&self, path: &str) -> Option<NodeId> {
+        path.split('/').filter(|name| !name.is_empty()).try_fold(
+            self.root(),
+            |id, name| match &self.node(id).kind {
+                Kind::Dir { children } => children.get(name).copied(),
+                Kind::File { .. } => None,
+            },
+        )
+    }
+
+    /// Absolute path of `id`, `/` for the root
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = Some(id);
+        while let Some(id) = current {
+            names.push(self.node(id).name.as_str());
+            current = self.node(id).parent;
+        }
+        names.reverse();
+
+        match names.len() {
+            1 => String::from("/"),
+            _ => names.join("/"),
+        }
+    }
+
+    /// `id` and everything below it, each directory before its contents
+    pub fn walk(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut stack = vec![id];
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            // pushed in reverse so they come out in alphabetical order
+            let children = self.children(id).collect::<Vec<_>>();
+            stack.extend(children.into_iter().rev());
+            Some(id)
+        })
+    }
+
+    /// Size of a file, or of everything below a directory
+    pub fn size(&self, id: NodeId) -> u32 {
+        self.walk(id)
+            .map(|id| match self.node(id).kind {
+                Kind::File { size } => size,
+                Kind::Dir { .. } => 0,
+            })
+            .sum()
+    }
+
+    /// Every directory with its size, computed in a single pass over the tree
+    pub fn dir_sizes(&self) -> Vec<(NodeId, u32)> {
+        let mut sizes = vec![0; self.nodes.len()];
+        let order = self.walk(self.root()).collect::<Vec<_>>();
+
+        // children come after their parents in the walk, so going backwards finishes them first
+        for &id in order.iter().rev() {
+            if let Kind::File { size } = self.node(id).kind {
+                sizes[id.0] = size;
+            }
+            if let Some(parent) = self.node(id).parent {
+                sizes[parent.0] += sizes[id.0];
+            }
+        }
+
+        order
+            .into_iter()
+            .filter(|&id| self.node(id).is_dir())
+            .map(|id| (id, sizes[id.0]))
+            .collect()
+    }
+
+    /// Lists `id` and everything below it the way the puzzle does, e.g. `- a (dir)`
+    pub fn listing(&self, id: NodeId) -> String {
+        let mut listing = String::new();
+        self.write_listing(&mut listing, id, 0).unwrap();
+        listing
+    }
+
+    fn write_listing(&self, out: &mut String, id: NodeId, depth: usize) -> fmt::Result {
+        let node = self.node(id);
+        let name = match node.parent {
+            Some(_) => node.name.as_str(),
+            None => "/",
+        };
+        match node.kind {
+            Kind::File { size } => writeln!(
+                out,
+                "{}- {} (file, size={})",
+                "  ".repeat(depth),
+                name,
+                size
+            ),
+            Kind::Dir { .. } => {
+                writeln!(out, "{}- {} (dir)", "  ".repeat(depth), name)?;
+                self.children(id)
+                    .try_for_each(|child| self.write_listing(out, child, depth + 1))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree_works() {
+        let mut filesystem = Filesystem::default();
+        let root = filesystem.root();
+        let a = filesystem.add_dir(root, "a");
+        let e = filesystem.add_dir(a, "e");
+        filesystem.add_file(e, "i", 584);
+        filesystem.add_file(a, "f", 29116);
+        filesystem.add_file(root, "b.txt", 100);
+        assert_eq!(filesystem.add_dir(root, "a"), a);
+
+        assert_eq!(filesystem.lookup("/a/e"), Some(e));
+        assert_eq!(filesystem.lookup("/"), Some(root));
+        assert_eq!(filesystem.lookup("/a/x"), None);
+        assert_eq!(filesystem.path(e), "/a/e");
+        assert_eq!(filesystem.size(a), 29700);
+        assert_eq!(
+            filesystem.dir_sizes(),
+            vec![(root, 29800), (a, 29700), (e, 584)]
+        );
+        assert_eq!(
+            filesystem
+                .walk(root)
+                .map(|id| filesystem.path(id))
+                .collect::<Vec<_>>(),
+            vec!["/", "/a", "/a/e", "/a/e/i", "/a/f", "/b.txt"]
+        );
+        assert_eq!(
+            filesystem.listing(a),
+            "- a (dir)\n  - e (dir)\n    - i (file, size=584)\n  - f (file, size=29116)\n"
+        );
+    }
+}
@@ -1,11 +1,14 @@
-use std::collections::BTreeMap;
+pub mod filesystem;
 
+use std::fmt::{self, Display};
+
+use filesystem::Filesystem;
 use nom::{
     branch::alt,
-    bytes::complete::{is_a, tag},
-    character::complete::{alpha1, newline},
-    multi::separated_list1,
-    sequence::separated_pair,
+    bytes::complete::{tag, take_till1},
+    character::complete::newline,
+    multi::{many0, separated_list1},
+    sequence::{preceded, separated_pair},
     IResult,
 };
 
@@ -24,13 +27,18 @@ enum Operation {
 
 #[derive(Debug)]
 enum Files {
-    File { size: u32 },
+    File { name: String, size: u32 },
     Dir(String),
 }
 
+/// Anything up to the next space or line break, names can hold any other character
+fn parse_name(input: &str) -> IResult<&str, &str> {
+    take_till1(char::is_whitespace)(input)
+}
+
 fn parse_cd(input: &str) -> IResult<&str, Operation> {
     let (input, _) = tag("$ cd ")(input)?;
-    let (input, dir) = alt((tag(".."), alpha1, tag("/")))(input)?;
+    let (input, dir) = parse_name(input)?;
 
     let operation = match dir {
         "/" => Operation::Cd(Cd::Root),
@@ -42,26 +50,29 @@ fn parse_cd(input: &str) -> IResult<&str, Operation> {
 }
 
 fn parse_file(input: &str) -> IResult<&str, Files> {
-    let (input, (size, _)) = separated_pair(
-        nom::character::complete::u32,
-        tag(" "),
-        is_a("qwertyuiopasdfghjklzxcvbnm."),
-    )(input)?;
-
-    Ok((input, Files::File { size }))
+    let (input, (size, name)) =
+        separated_pair(nom::character::complete::u32, tag(" "), parse_name)(input)?;
+
+    Ok((
+        input,
+        Files::File {
+            name: name.to_string(),
+            size,
+        },
+    ))
 }
 
 fn parse_dir(input: &str) -> IResult<&str, Files> {
     let (input, _) = tag("dir ")(input)?;
-    let (input, name) = alpha1(input)?;
+    let (input, name) = parse_name(input)?;
 
     Ok((input, Files::Dir(name.to_string())))
 }
 
 fn parse_ls(input: &str) -> IResult<&str, Operation> {
     let (input, _) = tag("$ ls")(input)?;
-    let (input, _) = newline(input)?;
-    let (input, files) = separated_list1(newline, alt((parse_file, parse_dir)))(input)?;
+    // an empty directory lists nothing
+    let (input, files) = many0(preceded(newline, alt((parse_file, parse_dir))))(input)?;
 
     Ok((input, Operation::Ls(files)))
 }
@@ -72,83 +83,181 @@ fn parse_operations(input: &str) -> IResult<&str, Vec<Operation>> {
     Ok((input, operations))
 }
 
-fn calculate_sizes(
-    (mut context, mut sizes): (Vec<String>, BTreeMap<Vec<String>, u32>),
-    operation: &Operation,
-) -> (Vec<String>, BTreeMap<Vec<String>, u32>) {
-    match operation {
-        Operation::Cd(Cd::Root) => {
-            context.push(String::from(""));
+/// A line of the transcript that isn't a command or a listing entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the transcript, starting at 1
+    pub line: usize,
+    pub text: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: can't read `{}`", self.line, self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Builds the tree of everything the transcript went through or listed
+pub fn build(input: &str) -> Result<Filesystem, ParseError> {
+    let operations = match parse_operations(input) {
+        Ok((rest, operations)) if rest.trim().is_empty() => Ok(operations),
+        Ok((rest, _)) => Err(rest),
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => Err(err.input),
+        Err(nom::Err::Incomplete(_)) => Err(input),
+    }
+    .map_err(|rest| {
+        // the parser stops at the start of the first line it can't read, or right before it
+        let rest = rest.trim_start_matches('\n');
+        let offset = input.len() - rest.len();
+        ParseError {
+            line: input[..offset].matches('\n').count() + 1,
+            text: rest.lines().next().unwrap_or_default().to_string(),
         }
-        Operation::Cd(Cd::Up) => {
-            context.pop();
+    })?;
+
+    let mut filesystem = Filesystem::default();
+    let mut cwd = filesystem.root();
+    for operation in operations.iter() {
+        match operation {
+            Operation::Cd(Cd::Root) => cwd = filesystem.root(),
+            Operation::Cd(Cd::Up) => cwd = filesystem.node(cwd).parent.unwrap_or(cwd),
+            Operation::Cd(Cd::Into(dir)) => cwd = filesystem.add_dir(cwd, dir),
+            Operation::Ls(files) => {
+                for file in files {
+                    match file {
+                        Files::File { name, size } => filesystem.add_file(cwd, name, *size),
+                        Files::Dir(name) => filesystem.add_dir(cwd, name),
+                    };
+                }
+            }
         }
-        Operation::Cd(Cd::Into(file)) => {
-            context.push(file.clone());
+    }
+
+    Ok(filesystem)
+}
+
+fn parse(input: &str) -> Filesystem {
+    build(input).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub struct Params {
+    /// Size of the filesystem
+    pub total_space: u32,
+    /// Free space the update needs
+    pub needed_space: u32,
+    /// Directories of at most this size are counted in part 1
+    pub size_threshold: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            total_space: 70_000_000,
+            needed_space: 30_000_000,
+            size_threshold: 100_000,
         }
-        Operation::Ls(files) => {
-            let sum = files
-                .iter()
-                .filter_map(|file| {
-                    if let Files::File { size, .. } = file {
-                        Some(size)
-                    } else {
-                        None
-                    }
-                })
-                .sum::<u32>();
-
-            for i in 0..context.len() {
-                sizes
-                    .entry(context[0..=i].to_vec())
-                    .and_modify(|size| *size += sum)
-                    .or_insert(sum);
+    }
+}
+
+impl Params {
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value
+            .parse::<u32>()
+            .map_err(|err| format!("{}: {}", key, err))?;
+        let (total_space, needed_space) = match key {
+            "total_space" => (value, self.needed_space),
+            "needed_space" => (self.total_space, value),
+            "size_threshold" => {
+                self.size_threshold = value;
+                return Ok(());
             }
+            _ => return Err(format!("unknown parameter `{}`", key)),
+        };
+        if needed_space > total_space {
+            return Err(format!(
+                "{}: needed_space {} can't be more than total_space {}",
+                key, needed_space, total_space
+            ));
         }
+        self.total_space = total_space;
+        self.needed_space = needed_space;
+        Ok(())
     }
-
-    (context, sizes)
 }
 
 pub fn process_part1(input: &str) -> String {
-    let (_input, operations) = parse_operations(input).unwrap();
-
-    let (_, sizes) = operations
-        .iter()
-        .fold((vec![], BTreeMap::new()), calculate_sizes);
+    process_part1_with(input, &Params::default())
+}
 
-    sizes
+pub fn process_part1_with(input: &str, params: &Params) -> String {
+    parse(input)
+        .dir_sizes()
         .iter()
-        .filter(|(_, &size)| size < 100_000)
+        .filter(|(_, size)| *size <= params.size_threshold)
         .map(|(_, size)| size)
         .sum::<u32>()
         .to_string()
 }
 
 pub fn process_part2(input: &str) -> String {
-    let (_input, operations) = parse_operations(input).unwrap();
+    process_part2_with(input, &Params::default())
+}
 
-    let (_, sizes) = operations
-        .iter()
-        .fold((vec![], BTreeMap::new()), calculate_sizes);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpaceError {
+    /// The files take up more than the whole filesystem
+    Overfull { used: u32, total: u32 },
+    /// Even deleting everything doesn't leave the space needed
+    TooLittle { needed: u32, total: u32 },
+}
+
+impl Display for SpaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpaceError::Overfull { used, total } => {
+                write!(f, "{} used doesn't fit in a filesystem of {}", used, total)
+            }
+            SpaceError::TooLittle { needed, total } => {
+                write!(f, "{} can't be freed in a filesystem of {}", needed, total)
+            }
+        }
+    }
+}
 
-    let total_space = 70_000_000;
-    let needed_space = 30_000_000;
+impl std::error::Error for SpaceError {}
 
-    let used_space = sizes.get(&vec![String::from("")]).unwrap();
+/// Size of the smallest directory that frees up enough space once deleted
+pub fn dir_to_delete(filesystem: &Filesystem, params: &Params) -> Result<u32, SpaceError> {
+    let used_space = filesystem.size(filesystem.root());
 
-    let free_space = total_space - used_space;
+    let free_space = params
+        .total_space
+        .checked_sub(used_space)
+        .ok_or(SpaceError::Overfull {
+            used: used_space,
+            total: params.total_space,
+        })?;
 
-    let need_to_be_freed_space = needed_space - free_space;
+    let need_to_be_freed_space = params.needed_space.saturating_sub(free_space);
 
-    let mut valid_dirs = sizes
+    filesystem
+        .dir_sizes()
         .iter()
-        .filter(|(_, &size)| size > need_to_be_freed_space)
-        .map(|(_, size)| size)
-        .collect::<Vec<&u32>>();
+        .map(|(_, size)| *size)
+        .filter(|&size| size >= need_to_be_freed_space)
+        .min()
+        .ok_or(SpaceError::TooLittle {
+            needed: params.needed_space,
+            total: params.total_space,
+        })
+}
 
-    valid_dirs.sort();
-    valid_dirs.iter().next().unwrap().to_string()
+pub fn process_part2_with(input: &str, params: &Params) -> String {
+    dir_to_delete(&parse(input), params)
+        .unwrap_or_else(|err| panic!("{}", err))
+        .to_string()
 }
 
 #[cfg(test)]
@@ -190,4 +299,105 @@ $ ls
         let result = process_part2(&INPUT);
         assert_eq!(result, "24933642");
     }
+
+    #[test]
+    fn params_work() {
+        let mut params = Params::default();
+        params.set("size_threshold", "600").unwrap();
+        params.set("needed_space", "50000000").unwrap();
+
+        assert_eq!(process_part1_with(INPUT, &params), "584");
+        assert_eq!(process_part2_with(INPUT, &params), "48381165");
+        assert!(params.set("total_space", "-1").is_err());
+        assert!(params.set("total_space", "40000000").is_err());
+        assert!(params.set("needed_space", "70000001").is_err());
+    }
+
+    #[test]
+    fn limits_are_inclusive() {
+        let mut params = Params::default();
+        // /a/e is 584, and 70000000 - 48381165 is already free
+        params.set("size_threshold", "584").unwrap();
+        params.set("needed_space", "21619419").unwrap();
+
+        assert_eq!(process_part1_with(INPUT, &params), "584");
+        assert_eq!(process_part2_with(INPUT, &params), "584");
+    }
+
+    #[test]
+    fn space_errors() {
+        let filesystem = build(INPUT).unwrap();
+        // set refuses both of these, so they're built directly
+        let params = Params {
+            total_space: 10,
+            needed_space: 0,
+            ..Params::default()
+        };
+        assert_eq!(
+            dir_to_delete(&filesystem, &params),
+            Err(SpaceError::Overfull {
+                used: 48381165,
+                total: 10
+            })
+        );
+
+        let params = Params {
+            needed_space: 70000001,
+            ..Params::default()
+        };
+        assert_eq!(
+            dir_to_delete(&filesystem, &params).unwrap_err().to_string(),
+            "70000001 can't be freed in a filesystem of 70000000"
+        );
+    }
+
+    #[test]
+    fn names_are_kept_whole() {
+        let filesystem =
+            build("$ cd /\n$ ls\n100 a.txt\n200 B.txt\n300 c\ndir x_2\n$ cd x_2\n$ ls\n5 y\n")
+                .unwrap();
+
+        assert_eq!(
+            filesystem
+                .walk(filesystem.root())
+                .map(|id| filesystem.path(id))
+                .collect::<Vec<_>>(),
+            vec!["/", "/B.txt", "/a.txt", "/c", "/x_2", "/x_2/y"]
+        );
+        assert_eq!(
+            build("$ cd /\n$ ls\n100 a.txt\n$ rm a.txt\n$ ls\n5 y"),
+            Err(ParseError {
+                line: 4,
+                text: String::from("$ rm a.txt")
+            })
+        );
+        assert_eq!(
+            build("$ cd /\n$ ls\nten a.txt").unwrap_err().to_string(),
+            "line 3: can't read `ten a.txt`"
+        );
+    }
+
+    #[test]
+    fn tree_is_built() {
+        let filesystem = build(INPUT).unwrap();
+        let e = filesystem.lookup("/a/e").unwrap();
+
+        assert_eq!(filesystem.size(e), 584);
+        assert_eq!(filesystem.size(filesystem.root()), 48381165);
+        assert_eq!(
+            filesystem
+                .children(filesystem.lookup("/d").unwrap())
+                .map(|id| filesystem.node(id).name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["d.ext", "d.log", "j", "k"]
+        );
+        assert!(filesystem.listing(filesystem.root()).starts_with(
+            "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+"
+        ));
+    }
 }
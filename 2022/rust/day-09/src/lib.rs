@@ -90,26 +90,59 @@ pub fn process_part1(input: &str) -> String {
     tail_moves.len().to_string()
 }
 
+pub struct Params {
+    /// Knots in the part 2 rope, head and tail included
+    pub knots: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { knots: 10 }
+    }
+}
+
+impl Params {
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "knots" => {
+                let knots = value
+                    .parse::<usize>()
+                    .map_err(|err| format!("{}: {}", key, err))?;
+                if knots < 2 {
+                    return Err(String::from("knots: a rope needs at least 2 knots"));
+                }
+                self.knots = knots;
+            }
+            _ => return Err(format!("unknown parameter `{}`", key)),
+        }
+        Ok(())
+    }
+}
+
 pub fn process_part2(input: &str) -> String {
+    process_part2_with(input, &Params::default())
+}
+
+pub fn process_part2_with(input: &str, params: &Params) -> String {
     let (_, moves) = parse_moves(input).unwrap();
 
     let mut tail_moves: BTreeSet<(i32, i32)> = BTreeSet::new();
 
-    const NUMBER_0F_KNOTS: usize = 10;
+    let number_of_knots = params.knots;
 
-    let mut rope_knots = vec![(0, 0); NUMBER_0F_KNOTS];
+    let mut rope_knots = vec![(0, 0); number_of_knots];
     tail_moves.insert(*rope_knots.last().unwrap());
 
     for current_move in moves {
         move_head(&mut rope_knots[0], current_move);
-        for i in 1..NUMBER_0F_KNOTS - 1 {
+        for i in 1..number_of_knots - 1 {
             move_tail(rope_knots[i - 1], &mut rope_knots[i]);
         }
         if move_tail(
-            rope_knots[NUMBER_0F_KNOTS - 2],
-            &mut rope_knots[NUMBER_0F_KNOTS - 1],
+            rope_knots[number_of_knots - 2],
+            &mut rope_knots[number_of_knots - 1],
         ) {
-            tail_moves.insert(rope_knots[NUMBER_0F_KNOTS - 1]);
+            tail_moves.insert(rope_knots[number_of_knots - 1]);
         }
     }
 
@@ -140,4 +173,13 @@ R 2";
         let result = process_part2(&INPUT);
         assert_eq!(result, "1");
     }
+
+    #[test]
+    fn params_work() {
+        let mut params = Params::default();
+        params.set("knots", "2").unwrap();
+
+        assert_eq!(process_part2_with(INPUT, &params), process_part1(INPUT));
+        assert!(params.set("knots", "1").is_err());
+    }
 }
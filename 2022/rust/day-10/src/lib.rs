@@ -31,12 +31,69 @@ fn parse_ops(input: &str) -> IResult<&str, Vec<Ops>> {
     Ok((input, ops))
 }
 
+pub struct Params {
+    /// Cycles during which the signal strength is measured
+    pub signal_cycles: Vec<u32>,
+    /// Pixels per CRT row
+    pub screen_width: u32,
+    /// Rows on the CRT
+    pub screen_height: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            signal_cycles: vec![20, 60, 100, 140, 180, 220],
+            screen_width: 40,
+            screen_height: 6,
+        }
+    }
+}
+
+impl Params {
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        // cycles and screen sizes all start at 1
+        let parse = |value: &str| match value.trim().parse::<u32>() {
+            Ok(0) => Err(format!("{}: has to be at least 1", key)),
+            Ok(value) => Ok(value),
+            Err(err) => Err(format!("{}: {}", key, err)),
+        };
+        match key {
+            "signal_cycles" => {
+                self.signal_cycles = value.split(',').map(parse).collect::<Result<_, _>>()?
+            }
+            "screen_width" => {
+                let screen_width = parse(value)?;
+                self.check_screen(screen_width, self.screen_height)?;
+                self.screen_width = screen_width;
+            }
+            "screen_height" => {
+                let screen_height = parse(value)?;
+                self.check_screen(self.screen_width, screen_height)?;
+                self.screen_height = screen_height;
+            }
+            _ => return Err(format!("unknown parameter `{}`", key)),
+        }
+        Ok(())
+    }
+
+    /// The screen is drawn one pixel per cycle, so its size has to fit a cycle count
+    fn check_screen(&self, width: u32, height: u32) -> Result<(), String> {
+        match width.checked_mul(height) {
+            Some(_) => Ok(()),
+            None => Err(format!("a {}x{} screen has too many pixels", width, height)),
+        }
+    }
+}
+
 pub fn process_part1(input: &str) -> String {
-    let (_, ops) = parse_ops(input).unwrap();
+    process_part1_with(input, &Params::default())
+}
 
-    const MAX_CYCLES: u32 = 230;
+pub fn process_part1_with(input: &str, params: &Params) -> String {
+    let (_, ops) = parse_ops(input).unwrap();
 
-    let interested_cycles = vec![20, 60, 100, 140, 180, 220];
+    let max_cycles = params.signal_cycles.iter().copied().max().unwrap_or(0);
 
     let mut cmd_remaining_cycles = 0;
     let mut current_ops_index: usize = 0;
@@ -46,12 +103,15 @@ pub fn process_part1(input: &str) -> String {
 
     let mut signal_strength = 0;
 
-    for i in 1..=MAX_CYCLES {
-        if interested_cycles.iter().any(|&c| c == i) {
+    for i in 1..=max_cycles {
+        if params.signal_cycles.contains(&i) {
             signal_strength += (i as i32) * X;
         }
         if cmd_remaining_cycles == 0 {
-            let next_cmd = ops[current_ops_index];
+            // once the program has run out, X keeps its last value
+            let Some(&next_cmd) = ops.get(current_ops_index) else {
+                continue;
+            };
             match next_cmd {
                 Ops::Noop => {
                     cmd_remaining_cycles = 0;
@@ -74,9 +134,13 @@ pub fn process_part1(input: &str) -> String {
 }
 
 pub fn process_part2(input: &str) -> String {
+    process_part2_with(input, &Params::default())
+}
+
+pub fn process_part2_with(input: &str, params: &Params) -> String {
     let (_, ops) = parse_ops(input).unwrap();
 
-    const MAX_CYCLES: u32 = 240;
+    let max_cycles = params.screen_width * params.screen_height;
 
     let mut cmd_remaining_cycles = 0;
     let mut current_ops_index: usize = 0;
@@ -86,19 +150,22 @@ pub fn process_part2(input: &str) -> String {
 
     let mut result: String = String::from("");
 
-    for i in 1..=MAX_CYCLES {
-        if (X - 1..=X + 1).contains(&((i as i32 - 1) % 40)) {
+    for i in 1..=max_cycles {
+        if (X - 1..=X + 1).contains(&(((i - 1) % params.screen_width) as i32)) {
             result = format!("{}#", result);
         } else {
             result = format!("{}.", result);
         }
 
-        if (i) % 40 == 0 {
+        if i % params.screen_width == 0 {
             result = format!("{}\n", result);
         }
 
         if cmd_remaining_cycles == 0 {
-            let next_cmd = ops[current_ops_index];
+            // once the program has run out, X keeps its last value
+            let Some(&next_cmd) = ops.get(current_ops_index) else {
+                continue;
+            };
             match next_cmd {
                 Ops::Noop => {
                     cmd_remaining_cycles = 0;
@@ -290,4 +357,36 @@ noop";
 "
         );
     }
+
+    #[test]
+    fn params_work() {
+        let mut params = Params::default();
+        params.set("signal_cycles", "20, 60").unwrap();
+        params.set("screen_width", "10").unwrap();
+        params.set("screen_height", "2").unwrap();
+
+        assert_eq!(process_part1_with(INPUT, &params), "1560");
+        assert_eq!(
+            process_part2_with(INPUT, &params),
+            "##..##..##\n....##....\n"
+        );
+        assert!(params.set("signal_cycles", "20,sixty").is_err());
+        assert!(params.set("signal_cycles", "0,20").is_err());
+        assert!(params.set("screen_width", "0").is_err());
+        assert!(params.set("screen_width", "100000").is_ok());
+        assert!(params.set("screen_height", "100000").is_err());
+    }
+
+    #[test]
+    fn runs_past_the_program() {
+        let mut params = Params::default();
+        params.set("signal_cycles", "20,300").unwrap();
+        params.set("screen_height", "7").unwrap();
+
+        // X ends at 17 and stays there, 20 * 21 + 300 * 17
+        assert_eq!(process_part1_with(INPUT, &params), "5520");
+        let crt = process_part2_with(INPUT, &params);
+        assert!(crt.starts_with(&process_part2(INPUT)));
+        assert_eq!(crt.lines().count(), 7);
+    }
 }
@@ -157,7 +157,44 @@ impl Monkey {
     }
 }
 
+pub struct Params {
+    /// Rounds played while the worry level drops after each inspection
+    pub part1_rounds: u32,
+    /// Rounds played without any relief
+    pub part2_rounds: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            part1_rounds: 20,
+            part2_rounds: 10_000,
+        }
+    }
+}
+
+impl Params {
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value
+            .parse::<u32>()
+            .map_err(|err| format!("{}: {}", key, err))?;
+        if value == 0 {
+            return Err(format!("{}: has to be at least 1", key));
+        }
+        match key {
+            "part1_rounds" => self.part1_rounds = value,
+            "part2_rounds" => self.part2_rounds = value,
+            _ => return Err(format!("unknown parameter `{}`", key)),
+        }
+        Ok(())
+    }
+}
+
 pub fn process_part1(input: &str) -> String {
+    process_part1_with(input, &Params::default())
+}
+
+pub fn process_part1_with(input: &str, params: &Params) -> String {
     let (_, mut monkeys) = separated_list1(multispace1, parse_monkey)(input).unwrap();
 
     let lcm = monkeys
@@ -165,7 +202,7 @@ pub fn process_part1(input: &str) -> String {
         .map(|monkey| monkey.test.divisor)
         .product::<u64>();
 
-    for _round in 0..20 {
+    for _round in 0..params.part1_rounds {
         for monkey_index in 0..monkeys.len() {
             for _ in 0..monkeys[monkey_index].items.len() {
                 let current_monkey = monkeys.get_mut(monkey_index).unwrap();
@@ -193,6 +230,10 @@ pub fn process_part1(input: &str) -> String {
 }
 
 pub fn process_part2(input: &str) -> String {
+    process_part2_with(input, &Params::default())
+}
+
+pub fn process_part2_with(input: &str, params: &Params) -> String {
     let (_, mut monkeys) = separated_list1(multispace1, parse_monkey)(input).unwrap();
 
     let lcm = monkeys
@@ -200,7 +241,7 @@ pub fn process_part2(input: &str) -> String {
         .map(|monkey| monkey.test.divisor)
         .product::<u64>();
 
-    for _round in 0..10000 {
+    for _round in 0..params.part2_rounds {
         for monkey_index in 0..monkeys.len() {
             for _ in 0..monkeys[monkey_index].items.len() {
                 let current_monkey = monkeys.get_mut(monkey_index).unwrap();
@@ -245,4 +286,15 @@ mod tests {
         let result = process_part2(&INPUT);
         assert_eq!(result, "2713310158");
     }
+
+    #[test]
+    fn params_work() {
+        let mut params = Params::default();
+        params.set("part1_rounds", "1").unwrap();
+        params.set("part2_rounds", "20").unwrap();
+
+        assert_eq!(process_part1_with(INPUT, &params), "20");
+        assert_eq!(process_part2_with(INPUT, &params), "10197");
+        assert!(params.set("part2_rounds", "0").is_err());
+    }
 }
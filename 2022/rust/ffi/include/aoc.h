@@ -0,0 +1,68 @@
+#ifndef AOC_H
+#define AOC_H
+
+/* Generated by cbindgen from src/lib.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result codes returned by `aoc_solve`
+ */
+typedef enum AocStatus {
+  AOC_STATUS_OK = 0,
+  /**
+   * `input_ptr`, `out_buf` or `out_written` is NULL
+   */
+  AOC_STATUS_NULL_POINTER = 1,
+  /**
+   * No solver is registered for the year and day
+   */
+  AOC_STATUS_UNKNOWN_PUZZLE = 2,
+  /**
+   * `part` is neither 1 nor 2
+   */
+  AOC_STATUS_INVALID_PART = 3,
+  /**
+   * The input is not valid UTF-8
+   */
+  AOC_STATUS_INVALID_INPUT = 4,
+  /**
+   * The solver panicked, `out_buf` holds the panic message
+   */
+  AOC_STATUS_SOLVER_FAILED = 5,
+  /**
+   * `out_buf` is too small, `out_written` holds the length needed (without the NUL)
+   */
+  AOC_STATUS_BUFFER_TOO_SMALL = 6,
+} AocStatus;
+
+/**
+ * Solves `part` of the puzzle for `year`/`day` with the `input_len` bytes at `input_ptr`.
+ *
+ * On success the answer is written to `out_buf` as a NUL-terminated string and its length
+ * to `out_written`.
+ *
+ * # Safety
+ *
+ * `input_ptr` must point to `input_len` readable bytes, `out_buf` to `out_len` writable
+ * bytes and `out_written` to a writable `size_t`.
+ */
+enum AocStatus aoc_solve(uint16_t year,
+                         uint8_t day,
+                         uint8_t part,
+                         const uint8_t *input_ptr,
+                         size_t input_len,
+                         uint8_t *out_buf,
+                         size_t out_len,
+                         size_t *out_written);
+
+/**
+ * Describes a status code as a static NUL-terminated string
+ */
+const char *aoc_status_message(int32_t status);
+
+#endif  /* AOC_H */
@@ -0,0 +1,153 @@
+//! C ABI over the solver registry, see `include/aoc.h` for the generated header.
+
+use std::{ffi::c_char, ptr, slice, str};
+
+use runner::{
+    registry::{self, Part},
+    solve,
+};
+
+/// Result codes returned by `aoc_solve`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AocStatus {
+    Ok = 0,
+    /// `input_ptr`, `out_buf` or `out_written` is NULL
+    NullPointer = 1,
+    /// No solver is registered for the year and day
+    UnknownPuzzle = 2,
+    /// `part` is neither 1 nor 2
+    InvalidPart = 3,
+    /// The input is not valid UTF-8
+    InvalidInput = 4,
+    /// The solver panicked, `out_buf` holds the panic message
+    SolverFailed = 5,
+    /// `out_buf` is too small, `out_written` holds the length needed (without the NUL)
+    BufferTooSmall = 6,
+}
+
+/// Copies `text` into `out_buf` followed by a NUL byte, if it fits
+unsafe fn write_out(text: &str, out_buf: *mut u8, out_len: usize, out_written: *mut usize) -> bool {
+    *out_written = text.len();
+    if text.len() + 1 > out_len {
+        return false;
+    }
+
+    ptr::copy_nonoverlapping(text.as_ptr(), out_buf, text.len());
+    *out_buf.add(text.len()) = 0;
+    true
+}
+
+/// Solves `part` of the puzzle for `year`/`day` with the `input_len` bytes at `input_ptr`.
+///
+/// On success the answer is written to `out_buf` as a NUL-terminated string and its length
+/// to `out_written`.
+///
+/// # Safety
+///
+/// `input_ptr` must point to `input_len` readable bytes, `out_buf` to `out_len` writable
+/// bytes and `out_written` to a writable `size_t`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn aoc_solve(
+    year: u16,
+    day: u8,
+    part: u8,
+    input_ptr: *const u8,
+    input_len: usize,
+    out_buf: *mut u8,
+    out_len: usize,
+    out_written: *mut usize,
+) -> AocStatus {
+    if input_ptr.is_null() || out_buf.is_null() || out_written.is_null() {
+        return AocStatus::NullPointer;
+    }
+    *out_written = 0;
+
+    let Some(puzzle) = registry::find(year, day) else {
+        return AocStatus::UnknownPuzzle;
+    };
+    let Some(part) = Part::from_number(part) else {
+        return AocStatus::InvalidPart;
+    };
+    let Ok(input) = str::from_utf8(slice::from_raw_parts(input_ptr, input_len)) else {
+        return AocStatus::InvalidInput;
+    };
+
+    let (text, status) = match solve::run(puzzle, part, input).answer {
+        Ok(answer) => (answer, AocStatus::Ok),
+        Err(message) => (message, AocStatus::SolverFailed),
+    };
+
+    if write_out(&text, out_buf, out_len, out_written) {
+        status
+    } else {
+        AocStatus::BufferTooSmall
+    }
+}
+
+/// Describes a status code as a static NUL-terminated string
+#[no_mangle]
+pub extern "C" fn aoc_status_message(status: i32) -> *const c_char {
+    // takes a plain integer, a C caller can pass values outside of `AocStatus`
+    let message: &'static [u8] = match status {
+        s if s == AocStatus::Ok as i32 => b"ok\0",
+        s if s == AocStatus::NullPointer as i32 => b"null pointer argument\0",
+        s if s == AocStatus::UnknownPuzzle as i32 => b"no solver for this puzzle\0",
+        s if s == AocStatus::InvalidPart as i32 => b"part must be 1 or 2\0",
+        s if s == AocStatus::InvalidInput as i32 => b"input is not valid UTF-8\0",
+        s if s == AocStatus::SolverFailed as i32 => b"solver failed\0",
+        s if s == AocStatus::BufferTooSmall as i32 => b"output buffer too small\0",
+        _ => b"unknown status\0",
+    };
+
+    message.as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(year: u16, day: u8, part: u8, input: &str, out_len: usize) -> (AocStatus, String) {
+        let mut out = vec![0u8; out_len.max(1)];
+        let mut written = 0;
+        let status = unsafe {
+            aoc_solve(
+                year,
+                day,
+                part,
+                input.as_ptr(),
+                input.len(),
+                out.as_mut_ptr(),
+                out_len,
+                &mut written,
+            )
+        };
+
+        let text = if status == AocStatus::BufferTooSmall {
+            written.to_string()
+        } else {
+            String::from_utf8(out[..written].to_vec()).unwrap()
+        };
+        (status, text)
+    }
+
+    #[test]
+    fn solves() {
+        assert_eq!(
+            solve(2022, 1, 2, "1000\n2000\n\n4000", 16),
+            (AocStatus::Ok, String::from("7000"))
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(solve(2022, 99, 1, "", 16).0, AocStatus::UnknownPuzzle);
+        assert_eq!(solve(2022, 1, 3, "", 16).0, AocStatus::InvalidPart);
+        assert_eq!(
+            solve(2022, 1, 1, "1000", 4),
+            (AocStatus::BufferTooSmall, String::from("4"))
+        );
+        assert_eq!(solve(2022, 1, 1, "elf", 256).0, AocStatus::SolverFailed);
+    }
+}
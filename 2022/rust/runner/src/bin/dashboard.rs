@@ -0,0 +1,299 @@
+use std::{collections::HashMap, fs, io, panic};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+use runner::{
+    alloc::CountingAllocator,
+    registry::{Part, Puzzle, PUZZLES},
+    solve::{self, Outcome, Status},
+};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const HELP: &str = "↑/↓ select  1/2 run part  a run all  PgUp/PgDn scroll  v visualization  q quit";
+
+#[derive(PartialEq)]
+enum View {
+    Input,
+    Visualization,
+}
+
+struct App {
+    list: ListState,
+    inputs: Vec<Result<String, String>>,
+    /// Recorded answers, read once so redraws don't go to the disk
+    expected: HashMap<(usize, Part), String>,
+    outcomes: HashMap<(usize, Part), Outcome>,
+    view: View,
+    scroll: u16,
+    message: String,
+}
+
+impl App {
+    fn new() -> App {
+        let inputs = PUZZLES
+            .iter()
+            .map(|puzzle| fs::read_to_string(puzzle.input_path()).map_err(|err| err.to_string()))
+            .collect();
+        let expected = PUZZLES
+            .iter()
+            .enumerate()
+            .flat_map(|(index, puzzle)| {
+                Part::ALL.into_iter().filter_map(move |part| {
+                    puzzle.expected(part).map(|answer| ((index, part), answer))
+                })
+            })
+            .collect();
+
+        App {
+            list: ListState::default().with_selected(Some(0)),
+            inputs,
+            expected,
+            outcomes: HashMap::new(),
+            view: View::Input,
+            scroll: 0,
+            message: String::from(HELP),
+        }
+    }
+
+    fn selected(&self) -> usize {
+        self.list.selected().unwrap_or(0)
+    }
+
+    fn select(&mut self, index: usize) {
+        self.list.select(Some(index));
+        self.view = View::Input;
+        self.scroll = 0;
+        self.message = String::from(HELP);
+    }
+
+    fn status(&self, index: usize, part: Part) -> Option<Status> {
+        self.outcomes
+            .get(&(index, part))
+            .map(|outcome| outcome.status(self.expected.get(&(index, part)).map(String::as_str)))
+    }
+
+    fn run(&mut self, index: usize, part: Part) {
+        let puzzle = &PUZZLES[index];
+        match &self.inputs[index] {
+            Ok(input) => {
+                let outcome = solve::run(puzzle, part, input);
+                self.outcomes.insert((index, part), outcome);
+            }
+            Err(err) => {
+                self.message = format!("{}: could not read input: {}", puzzle.name(), err);
+            }
+        }
+    }
+
+    fn toggle_visualization(&mut self) {
+        let puzzle = &PUZZLES[self.selected()];
+        if self.view == View::Visualization {
+            self.view = View::Input;
+        } else if puzzle.visualization.is_some() {
+            self.view = View::Visualization;
+        } else {
+            self.message = format!("{} has no visualization", puzzle.name());
+        }
+        self.scroll = 0;
+    }
+}
+
+fn status_span(status: Option<Status>) -> Span<'static> {
+    match status {
+        None => Span::raw("·").dark_gray(),
+        Some(Status::Solved) => Span::raw("●").yellow(),
+        Some(Status::Verified) => Span::raw("✓").green(),
+        Some(Status::Failing) => Span::raw("✗").red(),
+    }
+}
+
+fn status_text(status: Option<Status>) -> &'static str {
+    match status {
+        None => "not run",
+        Some(Status::Solved) => "solved",
+        Some(Status::Verified) => "verified",
+        Some(Status::Failing) => "failing",
+    }
+}
+
+/// Colours the digits and the punctuation of an input line, which is all the
+/// structure the puzzle inputs have
+fn highlight(line: &str) -> Line<'_> {
+    let class = |c: char| match c {
+        c if c.is_ascii_digit() || c == '-' => Some(Color::Yellow),
+        c if c.is_alphanumeric() || c.is_whitespace() => None,
+        _ => Some(Color::Cyan),
+    };
+
+    let span = |text, color: Option<Color>| match color {
+        Some(color) => Span::styled(text, Style::default().fg(color)),
+        None => Span::raw(text),
+    };
+
+    let mut spans = vec![];
+    let mut start = 0;
+    let mut current = line.chars().next().and_then(class);
+    for (i, c) in line.char_indices() {
+        if class(c) != current {
+            spans.push(span(&line[start..i], current));
+            start = i;
+            current = class(c);
+        }
+    }
+    spans.push(span(&line[start..], current));
+
+    Line::from(spans)
+}
+
+fn render_puzzles(frame: &mut Frame, area: Rect, app: &mut App) {
+    let items = PUZZLES
+        .iter()
+        .enumerate()
+        .map(|(index, puzzle)| {
+            let mut spans = vec![Span::raw(format!("{} {}  ", puzzle.year, puzzle.name()))];
+            for part in Part::ALL {
+                spans.push(status_span(app.status(index, part)));
+                spans.push(Span::raw(" "));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect::<Vec<_>>();
+
+    let list = List::new(items)
+        .block(Block::bordered().title(" Puzzles "))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(list, area, &mut app.list);
+}
+
+fn render_input(frame: &mut Frame, area: Rect, app: &App) {
+    let index = app.selected();
+    let puzzle = &PUZZLES[index];
+
+    let (title, text) = match (&app.view, &app.inputs[index]) {
+        (_, Err(err)) => (" Input ", Text::raw(err.clone()).red()),
+        (View::Input, Ok(input)) => (
+            " Input ",
+            Text::from(input.lines().map(highlight).collect::<Vec<_>>()),
+        ),
+        (View::Visualization, Ok(input)) => {
+            let visualize = puzzle
+                .visualization
+                .expect("only selectable with a visualization");
+            (" Visualization ", Text::raw(visualize(input)))
+        }
+    };
+
+    let paragraph = Paragraph::new(text)
+        .block(Block::bordered().title(title))
+        .scroll((app.scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+fn render_results(frame: &mut Frame, area: Rect, app: &App) {
+    let index = app.selected();
+    let puzzle: &Puzzle = &PUZZLES[index];
+
+    let mut lines = vec![];
+    for part in Part::ALL {
+        let status = app.status(index, part);
+        lines.push(Line::from(vec![
+            Span::raw(format!("Part {} ", part.number())).bold(),
+            status_span(status),
+            Span::raw(format!(" {}", status_text(status))),
+        ]));
+
+        if let Some(outcome) = app.outcomes.get(&(index, part)) {
+            match &outcome.answer {
+                Ok(answer) => {
+                    lines.extend(answer.lines().map(|line| Line::raw(format!("  {}", line))))
+                }
+                Err(message) => lines.push(Line::raw(format!("  panicked: {}", message)).red()),
+            }
+            lines.push(
+                Line::raw(format!(
+                    "  {:?}, {} allocs, {} bytes peak",
+                    outcome.elapsed,
+                    outcome.allocations.allocations,
+                    outcome.allocations.peak_bytes
+                ))
+                .dark_gray(),
+            );
+        }
+    }
+
+    let paragraph = Paragraph::new(lines).block(Block::bordered().title(format!(
+        " {} {} ",
+        puzzle.year,
+        puzzle.name()
+    )));
+
+    frame.render_widget(paragraph, area);
+}
+
+fn render(frame: &mut Frame, app: &mut App) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let [sidebar, content] =
+        Layout::horizontal([Constraint::Length(24), Constraint::Min(0)]).areas(main);
+    let [input, results] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(14)]).areas(content);
+
+    render_puzzles(frame, sidebar, app);
+    render_input(frame, input, app);
+    render_results(frame, results, app);
+    frame.render_widget(Paragraph::new(app.message.as_str()).dark_gray(), footer);
+}
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| render(frame, app))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let selected = app.selected();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => app.select(selected.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => app.select((selected + 1).min(PUZZLES.len() - 1)),
+            KeyCode::Char('1') => app.run(selected, Part::One),
+            KeyCode::Char('2') => app.run(selected, Part::Two),
+            KeyCode::Char('a') => {
+                for index in 0..PUZZLES.len() {
+                    for part in Part::ALL {
+                        app.run(index, part);
+                    }
+                }
+            }
+            KeyCode::PageDown => app.scroll = app.scroll.saturating_add(10),
+            KeyCode::PageUp => app.scroll = app.scroll.saturating_sub(10),
+            KeyCode::Char('v') => app.toggle_visualization(),
+            _ => {}
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    // panics are caught and shown as failing parts, the default hook would draw over the screen
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut App::new());
+    ratatui::restore();
+
+    result
+}
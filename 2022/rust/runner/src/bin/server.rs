@@ -0,0 +1,248 @@
+use std::{
+    env,
+    io::Read,
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
+
+use runner::{
+    registry::{self, Part, Puzzle},
+    solve,
+};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const USAGE: &str =
+    "usage: server [ADDRESS] [--max-body BYTES] [--timeout SECONDS] [--workers COUNT]";
+
+struct Options {
+    address: String,
+    max_body: u64,
+    timeout: Duration,
+    /// Requests handled at once, which is also the most solvers running at once
+    workers: usize,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        address: String::from("127.0.0.1:8080"),
+        max_body: 1024 * 1024,
+        timeout: Duration::from_secs(10),
+        workers: 4,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-body" => {
+                options.max_body = args
+                    .next()
+                    .and_then(|bytes| bytes.parse().ok())
+                    .ok_or("--max-body expects a number of bytes")?;
+            }
+            "--timeout" => {
+                options.timeout = args
+                    .next()
+                    .and_then(|seconds| seconds.parse().ok())
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                    .ok_or("--timeout expects a number of seconds")?;
+            }
+            "--workers" => {
+                options.workers = args
+                    .next()
+                    .and_then(|count| count.parse().ok())
+                    .filter(|&count| count > 0)
+                    .ok_or("--workers expects a positive number")?;
+            }
+            address => options.address = address.to_string(),
+        }
+    }
+
+    Ok(options)
+}
+
+/// Matches `/{year}/{day}/{part}` against the registry
+fn route(url: &str) -> Option<(&'static Puzzle, Part)> {
+    let mut segments = url.trim_matches('/').split('/');
+    let year = segments.next()?.parse().ok()?;
+    let day = segments.next()?.parse().ok()?;
+    let part = segments.next()?.parse().ok().and_then(Part::from_number)?;
+    if segments.next().is_some() {
+        return None;
+    }
+
+    registry::find(year, day).map(|puzzle| (puzzle, part))
+}
+
+fn read_body(request: &mut Request, max_body: u64) -> Result<String, (u16, String)> {
+    if request
+        .body_length()
+        .is_some_and(|length| length as u64 > max_body)
+    {
+        return Err((413, format!("input is larger than {} bytes", max_body)));
+    }
+
+    // the declared length can't be trusted, so read at most one byte past the limit
+    let mut body = vec![];
+    request
+        .as_reader()
+        .take(max_body + 1)
+        .read_to_end(&mut body)
+        .map_err(|err| (400, err.to_string()))?;
+    if body.len() as u64 > max_body {
+        return Err((413, format!("input is larger than {} bytes", max_body)));
+    }
+
+    String::from_utf8(body).map_err(|_| (400, String::from("input is not valid UTF-8")))
+}
+
+/// Solvers still running, including the ones whose request already timed out
+static RUNNING_SOLVERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Releases a solver slot once the solver thread is done, even if it panics
+struct SolverSlot;
+
+impl SolverSlot {
+    fn acquire(limit: usize) -> Option<SolverSlot> {
+        RUNNING_SOLVERS
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+                (running < limit).then_some(running + 1)
+            })
+            .ok()
+            .map(|_| SolverSlot)
+    }
+}
+
+impl Drop for SolverSlot {
+    fn drop(&mut self) {
+        RUNNING_SOLVERS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn handle(request: &mut Request, options: &Options) -> (u16, Value) {
+    if *request.method() != Method::Post {
+        return (405, json!({ "error": "only POST is supported" }));
+    }
+    let Some((puzzle, part)) = route(request.url()) else {
+        return (404, json!({ "error": "no solver for this puzzle" }));
+    };
+    let input = match read_body(request, options.max_body) {
+        Ok(input) => input,
+        Err((status, error)) => return (status, json!({ "error": error })),
+    };
+
+    // a solver that runs past the timeout can't be stopped, it's left to finish in the background
+    // and keeps its slot until then, so timed out solvers can't pile up past the worker count
+    let Some(slot) = SolverSlot::acquire(options.workers) else {
+        return (503, json!({ "error": "too many solvers running" }));
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _slot = slot;
+        sender.send(solve::run(puzzle, part, &input))
+    });
+
+    let mut body = json!({
+        "year": puzzle.year,
+        "day": puzzle.day,
+        "part": part.number(),
+    });
+    let Ok(outcome) = receiver.recv_timeout(options.timeout) else {
+        body["error"] = json!("solver timed out");
+        return (504, body);
+    };
+
+    body["elapsed_ms"] = json!(outcome.elapsed.as_secs_f64() * 1000.0);
+    match outcome.answer {
+        Ok(answer) => {
+            body["answer"] = json!(answer);
+            (200, body)
+        }
+        Err(error) => {
+            body["error"] = json!(error);
+            (422, body)
+        }
+    }
+}
+
+fn main() {
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(1);
+    });
+
+    let server = Server::http(&options.address).unwrap_or_else(|err| {
+        eprintln!("could not listen on {}: {}", options.address, err);
+        process::exit(1);
+    });
+    println!("listening on http://{}", options.address);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+
+    thread::scope(|scope| {
+        for _ in 0..options.workers {
+            scope.spawn(|| {
+                for mut request in server.incoming_requests() {
+                    let (status, body) = handle(&mut request, &options);
+                    println!("{} {} -> {}", request.method(), request.url(), status);
+
+                    let response = Response::from_string(body.to_string())
+                        .with_status_code(status)
+                        .with_header(content_type.clone());
+                    if let Err(err) = request.respond(response) {
+                        eprintln!("could not respond: {}", err);
+                    }
+                }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiny_http::TestRequest;
+
+    #[test]
+    fn routes_to_puzzles() {
+        let (puzzle, part) = route("/2022/7/2").unwrap();
+        assert_eq!((puzzle.year, puzzle.day, part), (2022, 7, Part::Two));
+        assert!(route("2022/01/1/").is_some());
+
+        assert!(route("/2022/7/3").is_none());
+        assert!(route("/2022/7").is_none());
+        assert!(route("/2022/7/1/extra").is_none());
+        assert!(route("/2021/1/1").is_none());
+        assert!(route("/year/1/1").is_none());
+    }
+
+    #[test]
+    fn reads_bodies_within_the_limit() {
+        let mut request = Request::from(TestRequest::new().with_body("1000\n2000"));
+        assert_eq!(read_body(&mut request, 9), Ok(String::from("1000\n2000")));
+
+        let mut request = Request::from(TestRequest::new().with_body("1000\n2000"));
+        assert_eq!(
+            read_body(&mut request, 8),
+            Err((413, String::from("input is larger than 8 bytes")))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_timeouts() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+
+        assert_eq!(
+            args(&["--timeout", "0.5"]).unwrap().timeout,
+            Duration::from_millis(500)
+        );
+        for timeout in ["-1", "inf", "NaN", "soon"] {
+            assert!(args(&["--timeout", timeout]).is_err(), "{}", timeout);
+        }
+        assert!(args(&["--workers", "0"]).is_err());
+    }
+}
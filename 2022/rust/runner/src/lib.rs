@@ -0,0 +1,3 @@
+pub mod alloc;
+pub mod registry;
+pub mod solve;
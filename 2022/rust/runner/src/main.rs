@@ -0,0 +1,136 @@
+use std::{env, fs, process};
+
+use runner::{
+    alloc::{AllocStats, CountingAllocator},
+    registry::{Part, Puzzle, PUZZLES},
+    solve::{self, Status},
+};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const USAGE: &str =
+    "usage: runner [DAY...] [--part 1|2] [--param KEY=VALUE]... [--alloc] [--report]";
+
+struct Options {
+    days: Vec<u8>,
+    parts: Vec<Part>,
+    params: Vec<(String, String)>,
+    report_allocations: bool,
+    report: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        days: vec![],
+        parts: Part::ALL.to_vec(),
+        params: vec![],
+        report_allocations: false,
+        report: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--alloc" => options.report_allocations = true,
+            "--report" => options.report = true,
+            "--part" => {
+                let part = args
+                    .next()
+                    .and_then(|part| part.parse::<u8>().ok())
+                    .and_then(Part::from_number)
+                    .ok_or("--part expects 1 or 2")?;
+                options.parts = vec![part];
+            }
+            "--param" => {
+                let param = args
+                    .next()
+                    .and_then(|param| {
+                        param
+                            .split_once('=')
+                            .map(|(key, value)| (key.to_string(), value.to_string()))
+                    })
+                    .ok_or("--param expects KEY=VALUE")?;
+                options.params.push(param);
+            }
+            day => {
+                let day = day
+                    .parse::<u8>()
+                    .map_err(|_| format!("unknown argument `{}`", day))?;
+                options.days.push(day);
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+fn format_allocations(stats: &AllocStats) -> String {
+    format!(
+        "{} allocs, {} bytes allocated, {} bytes peak",
+        stats.allocations, stats.allocated_bytes, stats.peak_bytes
+    )
+}
+
+fn run(puzzle: &Puzzle, options: &Options) {
+    let input = match fs::read_to_string(puzzle.input_path()) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("{}: could not read input: {}", puzzle.name(), err);
+            return;
+        }
+    };
+
+    if options.report {
+        if let Some(report) = puzzle.report {
+            println!("{} report\n\n{}", puzzle.name(), report(&input));
+        }
+        return;
+    }
+
+    for part in options.parts.iter() {
+        let outcome = solve::run_with(puzzle, *part, &input, &options.params);
+        // the recorded answers only hold for the default parameters
+        let expected = if options.params.is_empty() {
+            puzzle.expected(*part)
+        } else {
+            None
+        };
+        let status = match outcome.status(expected.as_deref()) {
+            Status::Solved => "solved",
+            Status::Verified => "verified",
+            Status::Failing => "failing",
+        };
+
+        // multi-line answers (e.g. day-10's CRT output) go below the header
+        let answer = match &outcome.answer {
+            Ok(answer) if answer.contains('\n') => format!("\n{}", answer.trim_end()),
+            Ok(answer) => answer.clone(),
+            Err(message) => format!("error: {}", message),
+        };
+
+        println!(
+            "{} part {} [{}] ({:?}): {}",
+            puzzle.name(),
+            part.number(),
+            status,
+            outcome.elapsed,
+            answer
+        );
+        if options.report_allocations {
+            println!("    {}", format_allocations(&outcome.allocations));
+        }
+    }
+}
+
+fn main() {
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(1);
+    });
+
+    for puzzle in PUZZLES {
+        if options.days.is_empty() || options.days.contains(&puzzle.day) {
+            run(puzzle, &options);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use std::{fs, path::PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn from_number(number: u8) -> Option<Part> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+/// `key=value` overrides for a puzzle's parameters
+pub type Params = [(String, String)];
+
+pub type SolveWithParams = fn(Part, &str, &Params) -> Result<String, String>;
+
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+    pub part1: fn(&str) -> String,
+    pub part2: fn(&str) -> String,
+    /// Solves a part with its parameters overridden, for days that have any
+    pub with_params: Option<SolveWithParams>,
+    /// Renders the input in a more visual form, for days that have one
+    pub visualization: Option<fn(&str) -> String>,
+    /// Summarizes the input beyond the answers, for days that have a report
+    pub report: Option<fn(&str) -> String>,
+}
+
+impl Puzzle {
+    /// Name of the crate holding the solution, e.g. `day-01`
+    pub fn name(&self) -> String {
+        format!("day-{:02}", self.day)
+    }
+
+    /// Location of the puzzle input, relative to the runner's crate
+    pub fn input_path(&self) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join(self.name())
+            .join("input.txt")
+    }
+
+    /// Location of the recorded answer for `part`, next to the input
+    pub fn answer_path(&self, part: Part) -> PathBuf {
+        self.input_path()
+            .with_file_name(format!("answer-part-{}.txt", part.number()))
+    }
+
+    /// The recorded answer for `part`, if there is one
+    pub fn expected(&self, part: Part) -> Option<String> {
+        fs::read_to_string(self.answer_path(part)).ok()
+    }
+
+    pub fn solve(&self, part: Part, input: &str) -> String {
+        match part {
+            Part::One => (self.part1)(input),
+            Part::Two => (self.part2)(input),
+        }
+    }
+
+    pub fn solve_with(&self, part: Part, input: &str, params: &Params) -> Result<String, String> {
+        if params.is_empty() {
+            return Ok(self.solve(part, input));
+        }
+
+        match self.with_params {
+            Some(with_params) => with_params(part, input, params),
+            None => Err(format!("{} has no parameters", self.name())),
+        }
+    }
+}
+
+/// Applies `params` over the defaults of a day's parameters
+fn configure<P: Default>(
+    params: &Params,
+    set: fn(&mut P, &str, &str) -> Result<(), String>,
+) -> Result<P, String> {
+    let mut configured = P::default();
+    for (key, value) in params {
+        set(&mut configured, key, value)?;
+    }
+    Ok(configured)
+}
+
+pub static PUZZLES: &[Puzzle] = &[
+    Puzzle {
+        year: 2022,
+        day: 1,
+        part1: day_01::process_part1,
+        part2: day_01::process_part2,
+        with_params: None,
+        visualization: None,
+        report: Some(day_01::report),
+    },
+    Puzzle {
+        year: 2022,
+        day: 2,
+        part1: |input| day_02::process_part1(input).to_string(),
+        part2: |input| day_02::process_part2(input).to_string(),
+        with_params: None,
+        visualization: None,
+        report: Some(day_02::decoding_report),
+    },
+    Puzzle {
+        year: 2022,
+        day: 3,
+        part1: |input| day_03::process_part1(input).to_string(),
+        part2: |input| day_03::process_part2(input).to_string(),
+        with_params: Some(|part, input, params| {
+            let params = configure(params, day_03::Params::set)?;
+            Ok(match part {
+                Part::One => day_03::process_part1_with(input, &params),
+                Part::Two => day_03::process_part2_with(input, &params),
+            }
+            .to_string())
+        }),
+        visualization: None,
+        report: Some(day_03::validation_report),
+    },
+    Puzzle {
+        year: 2022,
+        day: 4,
+        part1: day_04::process_part1,
+        part2: day_04::process_part2,
+        with_params: None,
+        visualization: None,
+        report: None,
+    },
+    Puzzle {
+        year: 2022,
+        day: 5,
+        part1: day_05::process_part1,
+        part2: day_05::process_part2,
+        with_params: None,
+        visualization: None,
+        report: None,
+    },
+    Puzzle {
+        year: 2022,
+        day: 6,
+        part1: day_06::process_part1,
+        part2: day_06::process_part2,
+        with_params: Some(|part, input, params| {
+            let params = configure(params, day_06::Params::set)?;
+            Ok(match part {
+                Part::One => day_06::process_part1_with(input, &params),
+                Part::Two => day_06::process_part2_with(input, &params),
+            })
+        }),
+        visualization: None,
+        report: None,
+    },
+    Puzzle {
+        year: 2022,
+        day: 7,
+        part1: day_07::process_part1,
+        part2: day_07::process_part2,
+        with_params: Some(|part, input, params| {
+            let params = configure(params, day_07::Params::set)?;
+            Ok(match part {
+                Part::One => day_07::process_part1_with(input, &params),
+                Part::Two => day_07::process_part2_with(input, &params),
+            })
+        }),
+        visualization: None,
+        report: None,
+    },
+    Puzzle {
+        year: 2022,
+        day: 8,
+        part1: day_08::process_part1,
+        part2: day_08::process_part2,
+        with_params: None,
+        visualization: None,
+        report: None,
+    },
+    Puzzle {
+        year: 2022,
+        day: 9,
+        part1: day_09::process_part1,
+        part2: day_09::process_part2,
+        with_params: Some(|part, input, params| {
+            // part 1's rope always has two knots
+            if part == Part::One {
+                return Err(String::from("day-09 part 1 has no parameters"));
+            }
+            let params = configure(params, day_09::Params::set)?;
+            Ok(day_09::process_part2_with(input, &params))
+        }),
+        visualization: None,
+        report: None,
+    },
+    Puzzle {
+        year: 2022,
+        day: 10,
+        part1: day_10::process_part1,
+        part2: day_10::process_part2,
+        with_params: Some(|part, input, params| {
+            let params = configure(params, day_10::Params::set)?;
+            Ok(match part {
+                Part::One => day_10::process_part1_with(input, &params),
+                Part::Two => day_10::process_part2_with(input, &params),
+            })
+        }),
+        // the CRT with lit pixels filled in, so the letters are easier to read
+        visualization: Some(|input| {
+            day_10::process_part2(input)
+                .replace('#', "█")
+                .replace('.', " ")
+        }),
+        report: None,
+    },
+    Puzzle {
+        year: 2022,
+        day: 11,
+        part1: day_11::process_part1,
+        part2: day_11::process_part2,
+        with_params: Some(|part, input, params| {
+            let params = configure(params, day_11::Params::set)?;
+            Ok(match part {
+                Part::One => day_11::process_part1_with(input, &params),
+                Part::Two => day_11::process_part2_with(input, &params),
+            })
+        }),
+        visualization: None,
+        report: None,
+    },
+    Puzzle {
+        year: 2022,
+        day: 12,
+        part1: day_12::process_part1,
+        part2: day_12::process_part2,
+        with_params: None,
+        visualization: None,
+        report: None,
+    },
+    Puzzle {
+        year: 2022,
+        day: 13,
+        part1: day_13::process_part1,
+        part2: day_13::process_part2,
+        with_params: None,
+        visualization: None,
+        report: None,
+    },
+];
+
+pub fn find(year: u16, day: u8) -> Option<&'static Puzzle> {
+    PUZZLES
+        .iter()
+        .find(|puzzle| puzzle.year == year && puzzle.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzles_are_unique() {
+        for (i, a) in PUZZLES.iter().enumerate() {
+            for b in PUZZLES.iter().skip(i + 1) {
+                assert!((a.year, a.day) != (b.year, b.day));
+            }
+        }
+    }
+
+    #[test]
+    fn inputs_exist() {
+        for puzzle in PUZZLES {
+            assert!(puzzle.input_path().exists(), "{}", puzzle.name());
+        }
+    }
+
+    #[test]
+    fn answers_are_recorded() {
+        for puzzle in PUZZLES {
+            for part in Part::ALL {
+                assert!(puzzle.expected(part).is_some(), "{}", puzzle.name());
+            }
+        }
+    }
+
+    #[test]
+    fn solves_by_part() {
+        let puzzle = find(2022, 1).unwrap();
+        let input = "1000\n2000\n\n4000";
+
+        assert_eq!(puzzle.solve(Part::One, input), "4000");
+        assert_eq!(puzzle.solve(Part::Two, input), "7000");
+    }
+
+    #[test]
+    fn params_are_not_ignored() {
+        let puzzle = find(2022, 9).unwrap();
+        let input = "R 4\nU 4";
+        let params = [(String::from("knots"), String::from("3"))];
+
+        assert!(puzzle.solve_with(Part::One, input, &params).is_err());
+        assert!(puzzle.solve_with(Part::Two, input, &params).is_ok());
+        assert!(puzzle.solve_with(Part::One, input, &[]).is_ok());
+    }
+
+    #[test]
+    fn visualizations_render() {
+        let puzzle = find(2022, 10).unwrap();
+        let input = fs::read_to_string(puzzle.input_path()).unwrap();
+        let crt = (puzzle.visualization.unwrap())(&input);
+
+        assert_eq!(crt.lines().count(), 6);
+        assert!(crt.lines().all(|row| row.chars().count() == 40));
+        assert!(crt.chars().all(|pixel| matches!(pixel, '█' | ' ' | '\n')));
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    alloc::{measure, AllocStats},
+    registry::{Params, Part, Puzzle},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Produced an answer, but there is no recorded answer to check it against
+    Solved,
+    /// Produced the recorded answer
+    Verified,
+    /// Panicked, or produced something other than the recorded answer
+    Failing,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The answer, or why there is none: a panic message or an invalid parameter
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+    pub allocations: AllocStats,
+}
+
+impl Outcome {
+    pub fn status(&self, expected: Option<&str>) -> Status {
+        match (&self.answer, expected) {
+            (Err(_), _) => Status::Failing,
+            (Ok(_), None) => Status::Solved,
+            (Ok(answer), Some(expected)) if answer.trim_end() == expected.trim_end() => {
+                Status::Verified
+            }
+            (Ok(_), Some(_)) => Status::Failing,
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("solver panicked")
+    }
+}
+
+/// Solves `part` of `puzzle`, timing it and catching any panic along the way
+pub fn run(puzzle: &Puzzle, part: Part, input: &str) -> Outcome {
+    run_with(puzzle, part, input, &[])
+}
+
+/// Same as [`run`], with the puzzle's parameters overridden by `params`
+pub fn run_with(puzzle: &Puzzle, part: Part, input: &str, params: &Params) -> Outcome {
+    let start = Instant::now();
+    let (answer, allocations) = measure(|| {
+        panic::catch_unwind(AssertUnwindSafe(|| puzzle.solve_with(part, input, params)))
+    });
+    let elapsed = start.elapsed();
+
+    Outcome {
+        answer: answer.map_err(panic_message).and_then(|answer| answer),
+        elapsed,
+        allocations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(answer: Result<&str, &str>) -> Outcome {
+        Outcome {
+            answer: answer.map(String::from).map_err(String::from),
+            elapsed: Duration::ZERO,
+            allocations: AllocStats::default(),
+        }
+    }
+
+    #[test]
+    fn status_works() {
+        assert_eq!(outcome(Ok("42")).status(None), Status::Solved);
+        assert_eq!(outcome(Ok("42")).status(Some("42\n")), Status::Verified);
+        assert_eq!(outcome(Ok("41")).status(Some("42\n")), Status::Failing);
+        assert_eq!(outcome(Err("boom")).status(Some("42")), Status::Failing);
+    }
+
+    #[test]
+    fn catches_panics() {
+        let puzzle = Puzzle {
+            year: 2022,
+            day: 0,
+            part1: |_| panic!("boom"),
+            part2: |input| input.to_string(),
+            with_params: None,
+            visualization: None,
+            report: None,
+        };
+
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let failed = run(&puzzle, Part::One, "");
+        panic::set_hook(hook);
+
+        assert_eq!(failed.answer, Err(String::from("boom")));
+        assert_eq!(run(&puzzle, Part::Two, "ok").answer, Ok(String::from("ok")));
+    }
+}